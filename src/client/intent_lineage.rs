@@ -0,0 +1,225 @@
+use alloy::primitives::U256;
+
+use super::MedusaRpcClient;
+use crate::error::{Error, MedusaError};
+use crate::types::intents::{Intent, IntentHistory, IntentId, IntentState};
+use crate::types::sol_types::All::AllErrors;
+
+/// One version of a partially fillable intent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntentVersion {
+    pub intent_id: IntentId,
+    pub intent: Intent,
+    pub state: IntentState,
+    pub history: IntentHistory,
+    /// Source amount spent by the solution that solved this version.
+    pub filled_amount: U256,
+}
+
+/// All versions of one logical order: the original intent followed by the remainders that
+/// partial fills left behind. Never empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntentLineage {
+    versions: Vec<IntentVersion>,
+}
+
+impl IntentLineage {
+    /// Builds the lineage from versions in order, oldest first, deriving filled amounts from
+    /// the source amount each version hands over to the next. `None` if `versions` is empty.
+    pub fn from_versions(
+        versions: Vec<(IntentId, Intent, IntentState, IntentHistory)>,
+    ) -> Option<Self> {
+        if versions.is_empty() {
+            return None;
+        }
+        let next_src_amounts: Vec<Option<U256>> = versions
+            .iter()
+            .skip(1)
+            .map(|(_, intent, ..)| Some(intent.src_amount))
+            .chain(std::iter::once(None))
+            .collect();
+        let versions = versions
+            .into_iter()
+            .zip(next_src_amounts)
+            .map(|((intent_id, intent, state, history), next_src_amount)| {
+                let filled_amount = if history.solve_timestamp.is_some() {
+                    intent
+                        .src_amount
+                        .saturating_sub(next_src_amount.unwrap_or_default())
+                } else {
+                    U256::ZERO
+                };
+                IntentVersion {
+                    intent_id,
+                    intent,
+                    state,
+                    history,
+                    filled_amount,
+                }
+            })
+            .collect();
+        Some(Self { versions })
+    }
+
+    /// Oldest first.
+    pub fn versions(&self) -> &[IntentVersion] {
+        &self.versions
+    }
+
+    pub fn original_id(&self) -> IntentId {
+        self.versions[0].intent_id
+    }
+
+    pub fn latest(&self) -> &IntentVersion {
+        self.versions
+            .last()
+            .expect("a lineage has at least one version")
+    }
+
+    /// The id of the newest version, which is the one that can still be filled if any.
+    pub fn latest_id(&self) -> IntentId {
+        self.latest().intent_id
+    }
+
+    pub fn filled_amount(&self) -> U256 {
+        self.versions.iter().map(|v| v.filled_amount).sum()
+    }
+
+    /// The source amount that can still be filled, zero once the latest version is closed.
+    pub fn remaining_amount(&self) -> U256 {
+        let latest = self.latest();
+        if latest.state == IntentState::Open {
+            latest.intent.src_amount
+        } else {
+            U256::ZERO
+        }
+    }
+}
+
+/// Fetches every version of the order that started with `intent_id`.
+///
+/// Versions are found through `remaining_intent_id`. If Medusa reports a later live version
+/// through `get_latest_liquidity` that the histories do not link to yet, it is appended. Only a
+/// not-found answer to that lookup is ignored; other failures are returned.
+pub async fn fetch_intent_lineage(
+    client: &impl MedusaRpcClient,
+    intent_id: IntentId,
+) -> Result<IntentLineage, Error> {
+    let mut versions: Vec<(IntentId, Intent, IntentState, IntentHistory)> = vec![];
+    let mut next = Some(intent_id);
+    while let Some(intent_id) = next {
+        let (intent_id, intent, state, history) = fetch_version(client, intent_id).await?;
+        next = history.remaining_intent_id;
+        versions.push((intent_id, intent, state, history));
+        // Guard against a history that links back into the lineage.
+        if versions.iter().any(|(id, ..)| Some(*id) == next) {
+            next = None;
+        }
+    }
+
+    match client
+        .get_latest_liquidity(intent_id)
+        .await
+        .map_err(MedusaError::from)
+    {
+        Ok(latest) if !versions.iter().any(|(id, ..)| *id == latest) => {
+            versions.push(fetch_version(client, latest).await?);
+        }
+        Ok(_) => {}
+        Err(e) if is_not_found(&e) => {
+            tracing::debug!("No latest liquidity version for {}: {}", intent_id, e)
+        }
+        Err(e) => return Err(e.into()),
+    }
+    Ok(IntentLineage::from_versions(versions).expect("the first version was fetched"))
+}
+
+/// Whether Medusa answered that it knows no such intent.
+fn is_not_found(err: &MedusaError) -> bool {
+    match err {
+        MedusaError::Revert(AllErrors::IntentBook__IntentNotFound(_)) => true,
+        MedusaError::InvalidParams(message) | MedusaError::Rejected { message, .. } => {
+            message.to_lowercase().contains("not found")
+        }
+        _ => false,
+    }
+}
+
+async fn fetch_version(
+    client: &impl MedusaRpcClient,
+    intent_id: IntentId,
+) -> Result<(IntentId, Intent, IntentState, IntentHistory), MedusaError> {
+    let (history, intent) = client.get_history_for_intent(intent_id).await?;
+    let state = client
+        .get_intent_status(intent_id)
+        .await?
+        .unwrap_or(IntentState::NonExistent);
+    Ok((intent_id, intent, state, history))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, B256};
+
+    use super::*;
+
+    #[test]
+    fn test_lineage_amounts() {
+        let intent = |src_amount: u64| {
+            Intent::simple_swap(
+                Address::repeat_byte(1),
+                U256::from(2_000),
+                U256::ZERO,
+                None,
+                Address::repeat_byte(0xaa),
+                U256::from(src_amount),
+                Address::repeat_byte(0xbb),
+                U256::from(1),
+            )
+        };
+        let solved = |remaining| IntentHistory {
+            publish_timestamp: Some(1),
+            solve_timestamp: Some(2),
+            remaining_intent_id: remaining,
+            ..Default::default()
+        };
+        let ids = [
+            B256::repeat_byte(1),
+            B256::repeat_byte(2),
+            B256::repeat_byte(3),
+        ];
+        let lineage = IntentLineage::from_versions(vec![
+            (
+                ids[0],
+                intent(100),
+                IntentState::Solved,
+                solved(Some(ids[1])),
+            ),
+            (
+                ids[1],
+                intent(60),
+                IntentState::Solved,
+                solved(Some(ids[2])),
+            ),
+            (
+                ids[2],
+                intent(15),
+                IntentState::Open,
+                IntentHistory::default(),
+            ),
+        ])
+        .unwrap();
+        let filled: Vec<U256> = lineage.versions().iter().map(|v| v.filled_amount).collect();
+        assert_eq!(filled, vec![U256::from(40), U256::from(45), U256::ZERO]);
+        assert_eq!(lineage.filled_amount(), U256::from(85));
+        assert_eq!(lineage.remaining_amount(), U256::from(15));
+        assert_eq!(lineage.latest_id(), ids[2]);
+        assert!(IntentLineage::from_versions(vec![]).is_none());
+
+        assert!(is_not_found(&MedusaError::Rejected {
+            code: -32000,
+            message: "Intent not found".into(),
+        }));
+        assert!(!is_not_found(&MedusaError::Timeout));
+    }
+}
@@ -0,0 +1,454 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use alloy::primitives::B256;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use super::MedusaRpcClient;
+use super::medusa_ws::{MedusaWsClient, broadcast_stream};
+use crate::error::{Error, MedusaError};
+use crate::types::intents::{IntentHistory, IntentId, IntentPhase, IntentState};
+use crate::types::ws::WsBroadcastMessage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntentProgress {
+    StatusChanged(IntentId, IntentState),
+    /// A step recorded in the intent's history. Errors are reported as
+    /// [`IntentPhase::Failed`] even when they can be retried.
+    Milestone {
+        intent_id: IntentId,
+        milestone: IntentPhase,
+        timestamp: Option<u64>,
+        tx_hash: Option<B256>,
+    },
+    /// The intent was partially filled and tracking continues with its remainder.
+    FollowedRemainder {
+        from: IntentId,
+        to: IntentId,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackerConfig {
+    /// How often the RPC is polled when no WS event arrives.
+    pub poll_interval: Duration,
+    /// The milestone at which tracking ends. Milestones after `Solved` are awaited on the last
+    /// intent of the lineage only.
+    pub until: IntentPhase,
+    /// Overall deadline for reaching `until`, across the whole lineage.
+    pub timeout: Duration,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(2),
+            until: IntentPhase::Solved,
+            timeout: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Where tracking ended.
+#[derive(Debug, Clone)]
+pub struct TrackOutcome {
+    /// The tracked intent followed by its remainders, in order.
+    pub lineage: Vec<IntentId>,
+    pub state: IntentState,
+    /// The history of the last intent in `lineage`.
+    pub history: IntentHistory,
+}
+
+impl TrackOutcome {
+    pub fn intent_id(&self) -> IntentId {
+        *self.lineage.last().unwrap()
+    }
+}
+
+/// Follows an intent until it reaches [`TrackerConfig::until`] or can no longer get there
+/// (cancelled, expired or failed). Gives up after [`TrackerConfig::timeout`].
+///
+/// The RPC is polled every [`TrackerConfig::poll_interval`]. With a WS client, status updates
+/// for the tracked intent trigger an immediate refresh.
+pub struct IntentTracker {
+    progress: broadcast::WeakSender<IntentProgress>,
+    first_progress: Mutex<Option<broadcast::Receiver<IntentProgress>>>,
+    task_handle: JoinHandle<Result<TrackOutcome, Error>>,
+}
+
+impl IntentTracker {
+    pub fn new<C>(client: C, intent_id: IntentId, config: TrackerConfig) -> Self
+    where
+        C: MedusaRpcClient + Send + Sync + 'static,
+    {
+        Self::spawn(client, None, intent_id, config)
+    }
+
+    pub fn with_ws<C>(
+        client: C,
+        ws_client: &MedusaWsClient,
+        intent_id: IntentId,
+        config: TrackerConfig,
+    ) -> Self
+    where
+        C: MedusaRpcClient + Send + Sync + 'static,
+    {
+        Self::spawn(
+            client,
+            Some(ws_client.subscribe().boxed()),
+            intent_id,
+            config,
+        )
+    }
+
+    fn spawn<C>(
+        client: C,
+        messages: Option<BoxStream<'static, WsBroadcastMessage>>,
+        intent_id: IntentId,
+        config: TrackerConfig,
+    ) -> Self
+    where
+        C: MedusaRpcClient + Send + Sync + 'static,
+    {
+        let (progress_send, progress_recv) = broadcast::channel(256);
+        let progress = progress_send.downgrade();
+        let timeout = config.timeout;
+        let task_handle = tokio::spawn(async move {
+            tokio::time::timeout(
+                timeout,
+                track(client, messages, intent_id, config, progress_send),
+            )
+            .await
+            .map_err(|_| Error::Timeout(timeout))
+        });
+        Self {
+            progress,
+            first_progress: Mutex::new(Some(progress_recv)),
+            task_handle,
+        }
+    }
+
+    /// Returns a stream of progress updates. The first call sees every update since tracking
+    /// started, later calls only see updates from then on. The stream ends with tracking.
+    pub fn progress(&self) -> impl Stream<Item = IntentProgress> + Send + 'static {
+        let recv = self
+            .first_progress
+            .lock()
+            .unwrap()
+            .take()
+            .or_else(|| self.progress.upgrade().map(|send| send.subscribe()));
+        match recv {
+            Some(recv) => broadcast_stream(recv).left_stream(),
+            None => futures::stream::empty().right_stream(),
+        }
+    }
+
+    /// Fails with [`Error::Timeout`] if the intent was not tracked to the end in time.
+    pub async fn await_terminal(self) -> Result<TrackOutcome, Error> {
+        // The task is never aborted, so it can only fail to join by panicking.
+        self.task_handle
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+}
+
+async fn track<C: MedusaRpcClient>(
+    client: C,
+    mut messages: Option<BoxStream<'static, WsBroadcastMessage>>,
+    intent_id: IntentId,
+    config: TrackerConfig,
+    progress: broadcast::Sender<IntentProgress>,
+) -> TrackOutcome {
+    let mut lineage = vec![intent_id];
+    let mut state = None;
+    let mut seen = IntentHistory::default();
+    let mut poll = tokio::time::interval(config.poll_interval);
+    loop {
+        let intent_id = *lineage.last().unwrap();
+        tokio::select! {
+            _ = poll.tick() => {}
+            message = next_message(&mut messages) => match message {
+                Some(WsBroadcastMessage::IntentStatusUpdated(id, _)) if id == intent_id => {}
+                Some(WsBroadcastMessage::IntentsSolved(ids, _)) if ids.contains(&intent_id) => {}
+                Some(_) => continue,
+                None => {
+                    messages = None;
+                    continue;
+                }
+            },
+        }
+
+        let (new_state, history) = match refresh(&client, intent_id).await {
+            Ok(Some(refreshed)) => refreshed,
+            // Not indexed yet.
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("Failed to refresh intent {}: {}", intent_id, e);
+                continue;
+            }
+        };
+        if state.as_ref() != Some(&new_state) {
+            let _ = progress.send(IntentProgress::StatusChanged(intent_id, new_state.clone()));
+            state = Some(new_state.clone());
+        }
+        let mut reached_until = false;
+        for (milestone, timestamp, tx_hash) in milestones(&history) {
+            reached_until |= milestone == config.until;
+            if !milestones(&seen).iter().any(|(m, ..)| *m == milestone) {
+                let _ = progress.send(IntentProgress::Milestone {
+                    intent_id,
+                    milestone,
+                    timestamp,
+                    tx_hash,
+                });
+            }
+        }
+        seen = history.clone();
+
+        if let Some(remaining) = history.remaining_intent_id
+            && history.solve_timestamp.is_some()
+            && !lineage.contains(&remaining)
+        {
+            let _ = progress.send(IntentProgress::FollowedRemainder {
+                from: intent_id,
+                to: remaining,
+            });
+            lineage.push(remaining);
+            state = None;
+            seen = IntentHistory::default();
+            poll.reset_immediately();
+            continue;
+        }
+        if reached_until || has_ended(&new_state, &history) {
+            return TrackOutcome {
+                lineage,
+                state: new_state,
+                history,
+            };
+        }
+    }
+}
+
+async fn next_message(
+    messages: &mut Option<BoxStream<'static, WsBroadcastMessage>>,
+) -> Option<WsBroadcastMessage> {
+    match messages {
+        Some(messages) => messages.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn refresh<C: MedusaRpcClient>(
+    client: &C,
+    intent_id: IntentId,
+) -> Result<Option<(IntentState, IntentHistory)>, MedusaError> {
+    let Some(state) = client.get_intent_status(intent_id).await? else {
+        return Ok(None);
+    };
+    if state == IntentState::NonExistent {
+        return Ok(None);
+    }
+    let (history, _) = client.get_history_for_intent(intent_id).await?;
+    Ok(Some((state, history)))
+}
+
+/// Whether the intent can no longer progress. The state can stay `Solved` after a terminal
+/// failure, so the history decides; expiry only shows in the state.
+fn has_ended(state: &IntentState, history: &IntentHistory) -> bool {
+    history.phase().is_terminal() || matches!(state, IntentState::Cancelled | IntentState::Expired)
+}
+
+/// The milestones `history` has reached, in lifecycle order.
+fn milestones(history: &IntentHistory) -> Vec<(IntentPhase, Option<u64>, Option<B256>)> {
+    let mut reached = vec![];
+    let mut push = |milestone, timestamp: Option<u64>, tx_hash| {
+        if timestamp.is_some() {
+            reached.push((milestone, timestamp, tx_hash));
+        }
+    };
+    push(
+        IntentPhase::Published,
+        history.publish_timestamp,
+        history.publish_tx_hash,
+    );
+    push(
+        IntentPhase::Solved,
+        history.solve_timestamp,
+        history.solve_tx_hash,
+    );
+    push(
+        IntentPhase::Redeemed,
+        history.redeem_timestamp,
+        history.redeem_tx_hash,
+    );
+    push(
+        IntentPhase::Withdrawn,
+        history.withdraw_timestamp,
+        history.withdraw_tx_hash,
+    );
+    push(
+        IntentPhase::WithdrawnToSpoke,
+        history.withdraw_to_spoke_timestamp,
+        None,
+    );
+    push(
+        IntentPhase::Cancelled,
+        history.cancel_timestamp,
+        history.cancel_tx_hash,
+    );
+    if let Some(error_type) = &history.error_type {
+        push(
+            IntentPhase::Failed(error_type.clone()),
+            history.error_timestamp,
+            history.error_tx_hash,
+        );
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::intents::IntentErrorType;
+    #[cfg(feature = "mock")]
+    use {
+        crate::client::medusa_ws::create_medusa_ws_client,
+        crate::mock::MockMedusaWs,
+        crate::test_support::{add_solver, propose_intent, start_mock_medusa, test_deployment},
+        crate::types::intent_builder::IntentBuilder,
+        crate::types::intents::{FillStructure, Intent},
+        crate::types::solution::Solution,
+        alloy::primitives::{Address, U256},
+        alloy::signers::local::PrivateKeySigner,
+    };
+
+    #[test]
+    fn test_milestones_in_lifecycle_order() {
+        let history = IntentHistory {
+            publish_timestamp: Some(1),
+            solve_timestamp: Some(2),
+            withdraw_to_spoke_timestamp: Some(4),
+            error_timestamp: Some(3),
+            error_type: Some(IntentErrorType::Withdraw),
+            ..Default::default()
+        };
+        let reached: Vec<IntentPhase> = milestones(&history).into_iter().map(|(m, ..)| m).collect();
+        assert_eq!(
+            reached,
+            vec![
+                IntentPhase::Published,
+                IntentPhase::Solved,
+                IntentPhase::WithdrawnToSpoke,
+                IntentPhase::Failed(IntentErrorType::Withdraw),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_terminal_failure_ends_solved_intent() {
+        let mut history = IntentHistory {
+            publish_timestamp: Some(1),
+            solve_timestamp: Some(2),
+            redeem_timestamp: Some(3),
+            withdraw_timestamp: Some(4),
+            ..Default::default()
+        };
+        assert!(!has_ended(&IntentState::Solved, &history));
+        history.error_timestamp = Some(5);
+        history.error_type = Some(IntentErrorType::Withdraw);
+        assert!(!has_ended(&IntentState::Solved, &history));
+        history.error_type = Some(IntentErrorType::WithdrawToSpoke);
+        assert!(has_ended(&IntentState::Solved, &history));
+        assert!(has_ended(&IntentState::Expired, &IntentHistory::default()));
+    }
+
+    /// A solution paying `intent`'s author back `spent` and keeping the rest as a remainder.
+    #[cfg(feature = "mock")]
+    async fn partial_fill(
+        intent: &Intent,
+        spent: U256,
+        signer: &PrivateKeySigner,
+    ) -> (crate::types::solution::SignedSolution, Option<IntentId>) {
+        let mut builder = Solution::builder();
+        let input = builder.add_input(intent.clone());
+        builder.deliver(input, input, spent).unwrap();
+        let remainder =
+            (spent < intent.src_amount).then(|| builder.remainder(input, spent).unwrap().index());
+        let solution = builder.build_unchecked();
+        let remainder = remainder.map(|idx| solution.intent_outputs[idx].intent_id());
+        (solution.sign(signer).await, remainder)
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn test_track_against_mock_medusa() {
+        let (medusa, client, _handle) = start_mock_medusa().await;
+        let signer = PrivateKeySigner::random();
+        let (signed, intent_id) = propose_intent(
+            &client,
+            &signer,
+            IntentBuilder::new(signer.address())
+                .sell(Address::repeat_byte(0xaa), U256::from(100))
+                .receive(Address::repeat_byte(0xbb), U256::from(10))
+                .fill_structure(FillStructure::PercentageFilled)
+                .valid_for(Duration::from_secs(600)),
+        )
+        .await;
+
+        // Polling only: the partial fill is picked up and tracking follows the remainder, which
+        // then times out as nothing solves it.
+        let config = TrackerConfig {
+            poll_interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let tracker = IntentTracker::new(client.clone(), intent_id, config);
+        let mut progress = Box::pin(tracker.progress());
+        let (solution, remainder) = partial_fill(&signed.intent, U256::from(60), &signer).await;
+        let remainder = remainder.unwrap();
+        medusa.settle(solution).unwrap();
+        loop {
+            if let Some(IntentProgress::FollowedRemainder { from, to }) = progress.next().await {
+                assert_eq!((from, to), (intent_id, remainder));
+                break;
+            }
+        }
+        assert!(matches!(
+            tracker.await_terminal().await,
+            Err(Error::Timeout(_))
+        ));
+
+        // With a WS client and no polling after the first refresh, a status update triggers it.
+        let ws_server = MockMedusaWs::start(test_deployment()).await.unwrap();
+        let add_solver = add_solver(&signer).await;
+        let ws_client = create_medusa_ws_client(ws_server.url(), add_solver)
+            .await
+            .unwrap();
+        ws_server.wait_for_sessions(1).await;
+        let config = TrackerConfig {
+            poll_interval: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let tracker = IntentTracker::with_ws(client.clone(), &ws_client, remainder, config);
+        let mut progress = Box::pin(tracker.progress());
+        assert!(matches!(
+            progress.next().await,
+            Some(IntentProgress::StatusChanged(_, IntentState::Open))
+        ));
+        let remainder_intent = client.get_intent(remainder).await.unwrap().unwrap();
+        let (solution, _) =
+            partial_fill(&remainder_intent, remainder_intent.src_amount, &signer).await;
+        medusa.settle(solution).unwrap();
+        ws_server.broadcast(WsBroadcastMessage::IntentStatusUpdated(
+            remainder,
+            IntentState::Solved,
+        ));
+        let outcome = tracker.await_terminal().await.unwrap();
+        assert_eq!(outcome.lineage, vec![remainder]);
+        assert_eq!(outcome.state, IntentState::Solved);
+        assert!(outcome.history.solve_tx_hash.is_some());
+    }
+}
@@ -0,0 +1,105 @@
+//! Bulk lookups sent as a single JSON-RPC batch request.
+
+use jsonrpsee::core::client::{ClientT, Error as ClientError};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::rpc_params;
+use serde::de::DeserializeOwned;
+
+use super::medusa_rpc::methods;
+use crate::error::MedusaError;
+use crate::types::intents::{Intent, IntentId, IntentState};
+use crate::types::solution::SignedSolution;
+
+/// One result per requested id, in request order. The outer error means the batch as a whole
+/// failed, e.g. on a transport error.
+pub type BatchResult<T> = Result<Vec<Result<T, MedusaError>>, MedusaError>;
+
+async fn batch_by_intent_id<T>(
+    client: &impl ClientT,
+    method: &str,
+    intent_ids: &[IntentId],
+) -> BatchResult<T>
+where
+    T: DeserializeOwned + std::fmt::Debug,
+{
+    if intent_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut batch = BatchRequestBuilder::new();
+    for intent_id in intent_ids {
+        batch
+            .insert(method, rpc_params![intent_id])
+            .map_err(ClientError::ParseError)?;
+    }
+    let responses = client.batch_request::<T>(batch).await?;
+    Ok(responses
+        .into_iter()
+        .map(|response| response.map_err(|e| ClientError::Call(e.into_owned()).into()))
+        .collect())
+}
+
+/// Batched `get_intent`.
+pub async fn get_intents(
+    client: &impl ClientT,
+    intent_ids: &[IntentId],
+) -> BatchResult<Option<Intent>> {
+    batch_by_intent_id(client, methods::GET_INTENT, intent_ids).await
+}
+
+/// Batched `get_intent_status`.
+pub async fn get_intent_statuses(
+    client: &impl ClientT,
+    intent_ids: &[IntentId],
+) -> BatchResult<Option<IntentState>> {
+    batch_by_intent_id(client, methods::GET_INTENT_STATUS, intent_ids).await
+}
+
+/// Batched `get_solution_for_intent`.
+pub async fn get_solutions_for_intents(
+    client: &impl ClientT,
+    intent_ids: &[IntentId],
+) -> BatchResult<Option<SignedSolution>> {
+    batch_by_intent_id(client, methods::GET_SOLUTION, intent_ids).await
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::primitives::{Address, U256};
+    use alloy::signers::local::PrivateKeySigner;
+
+    use super::*;
+    use crate::test_support::{propose_intent, start_mock_medusa};
+    use crate::types::intent_builder::IntentBuilder;
+
+    #[tokio::test]
+    async fn test_batched_lookups() {
+        let (_, client, _handle) = start_mock_medusa().await;
+        let signer = PrivateKeySigner::random();
+        let (signed, intent_id) = propose_intent(
+            &client,
+            &signer,
+            IntentBuilder::new(signer.address())
+                .sell(Address::repeat_byte(0xaa), U256::from(100))
+                .receive(Address::repeat_byte(0xbb), U256::from(1))
+                .valid_for(Duration::from_secs(600)),
+        )
+        .await;
+
+        let ids = [intent_id, IntentId::ZERO];
+        let intents = get_intents(&client, &ids).await.unwrap();
+        assert_eq!(intents.len(), 2);
+        assert_eq!(intents[0].as_ref().unwrap(), &Some(signed.intent));
+        assert_eq!(intents[1].as_ref().unwrap(), &None);
+
+        let statuses = get_intent_statuses(&client, &ids).await.unwrap();
+        assert_eq!(statuses[0].as_ref().unwrap(), &Some(IntentState::Open));
+        assert!(
+            get_solutions_for_intents(&client, &[])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+use std::time::Duration;
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use jsonrpsee::core::client::{BatchResponse, ClientT, Error as ClientError};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+#[cfg(any(feature = "ws-native-tls", feature = "ws-rustls"))]
+use jsonrpsee::ws_client::{PingConfig, WsClient, WsClientBuilder};
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+
+use super::medusa_rpc::methods;
+use crate::error::MedusaError;
+
+/// Methods that only read state and can therefore be retried safely.
+///
+/// Everything else (proposing or cancelling intents, withdrawals, vault operations) is sent
+/// exactly once, as a retry after a lost response could apply it twice.
+pub const IDEMPOTENT_METHODS: &[&str] = &[
+    methods::GET_VAULT_SHARE_PRICE_DATA,
+    methods::GET_MTOKEN_BALANCE_IN_VAULT,
+    methods::GET_DEPOSITOR_VAULT_SHARES,
+    methods::GET_VAULT_TOTAL_ASSET_VALUE,
+    methods::GET_VAULT_TOTAL_SHARES,
+    methods::PREVIEW_DEPOSIT_TO_VAULT,
+    methods::PREVIEW_MAXIMUM_WITHDRAW_FROM_VAULT,
+    methods::GET_MTOKEN_BALANCE_BY_AUTHOR,
+    methods::GET_SOLUTION,
+    methods::GET_CONNECTED_SOLVERS,
+    methods::GET_INTENT_IDS_BY_AUTHOR,
+    methods::GET_ACTIVE_INTENTS_BY_AUTHOR,
+    methods::GET_LIQUIDITY_INTENTS_BY_AUTHOR,
+    methods::GET_BRIDGE_INTENTS_BY_AUTHOR,
+    methods::GET_LATEST_LIQUIDITY_VERSION,
+    methods::GET_SOLUTIONS_FOR_SOLVER,
+    methods::GET_INTENT,
+    methods::GET_INTENT_STATUS,
+    methods::QUERY_REFINEMENT,
+    methods::GET_HISTORY,
+    methods::GET_FAILED_INTENTS_SINCE,
+    methods::GET_NONCE,
+];
+
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Retries after the first attempt. `0` disables retrying.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Settings for [`MedusaClient`].
+#[derive(Debug, Clone)]
+pub struct MedusaClientConfig {
+    pub request_timeout: Duration,
+    pub connect_timeout: Duration,
+    /// Sent with every request, e.g. an API key.
+    pub headers: HeaderMap,
+    pub max_request_size: u32,
+    pub max_response_size: u32,
+    pub max_concurrent_requests: usize,
+    /// Applied to [`IDEMPOTENT_METHODS`] failing with a transport error or timeout.
+    pub retry: RetryConfig,
+}
+
+impl Default for MedusaClientConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(5),
+            connect_timeout: Duration::from_secs(10),
+            headers: HeaderMap::new(),
+            max_request_size: 10 * 1024 * 1024,
+            max_response_size: 10 * 1024 * 1024,
+            max_concurrent_requests: 256,
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+impl MedusaClientConfig {
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, MedusaError> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| MedusaError::Config(format!("header name {name:?}: {e}")))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| MedusaError::Config(format!("header {name}: {e}")))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn http_client_builder(&self) -> HttpClientBuilder {
+        HttpClientBuilder::default()
+            .request_timeout(self.request_timeout)
+            .connect_timeout(self.connect_timeout)
+            .set_headers(self.headers.clone())
+            .max_request_size(self.max_request_size)
+            .max_response_size(self.max_response_size)
+            .max_concurrent_requests(self.max_concurrent_requests)
+    }
+
+    /// The same settings for a WebSocket connection, with keep-alive pings enabled.
+    #[cfg(any(feature = "ws-native-tls", feature = "ws-rustls"))]
+    pub fn ws_client_builder(&self) -> WsClientBuilder {
+        WsClientBuilder::default()
+            .request_timeout(self.request_timeout)
+            .connection_timeout(self.connect_timeout)
+            .set_headers(self.headers.clone())
+            .max_request_size(self.max_request_size)
+            .max_response_size(self.max_response_size)
+            .max_concurrent_requests(self.max_concurrent_requests)
+            .enable_ws_ping(PingConfig::default())
+    }
+
+    pub fn build(self, url: impl AsRef<str>) -> Result<MedusaClient, MedusaError> {
+        create_medusa_rpc_client_with_config(url, self)
+    }
+}
+
+/// Connects to Medusa's JSON-RPC API over a single persistent WebSocket connection.
+///
+/// The returned client implements [`MedusaRpcClient`](super::MedusaRpcClient). Requests are
+/// not retried: once the connection drops every call fails with
+/// [`ClientError::RestartNeeded`] and a new client has to be created. Available with either
+/// the `ws-native-tls` or the `ws-rustls` feature. jsonrpsee's WS transport has no native-tls
+/// backend, so `wss://` URLs always go through rustls.
+#[cfg(any(feature = "ws-native-tls", feature = "ws-rustls"))]
+pub async fn create_medusa_ws_rpc_client(
+    url: impl AsRef<str>,
+    config: MedusaClientConfig,
+) -> Result<WsClient, MedusaError> {
+    Ok(config.ws_client_builder().build(url).await?)
+}
+
+/// Connects to Medusa over HTTP using `config`.
+pub fn create_medusa_rpc_client_with_config(
+    url: impl AsRef<str>,
+    config: MedusaClientConfig,
+) -> Result<MedusaClient, MedusaError> {
+    let inner = config.http_client_builder().build(url)?;
+    Ok(MedusaClient {
+        inner,
+        retry: config.retry,
+    })
+}
+
+/// HTTP client for Medusa that retries [`IDEMPOTENT_METHODS`] with exponential backoff.
+/// Batches are retried as a whole if they only contain such methods.
+///
+/// Implements [`MedusaRpcClient`](super::MedusaRpcClient) like a plain [`HttpClient`].
+#[derive(Debug, Clone)]
+pub struct MedusaClient {
+    inner: HttpClient,
+    retry: RetryConfig,
+}
+
+impl MedusaClient {
+    pub fn inner(&self) -> &HttpClient {
+        &self.inner
+    }
+}
+
+/// Parameters serialized once so they can be re-sent on every attempt.
+#[derive(Clone)]
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+fn is_retryable(error: &ClientError) -> bool {
+    matches!(
+        error,
+        ClientError::Transport(_) | ClientError::RequestTimeout
+    )
+}
+
+impl MedusaClient {
+    /// Runs `attempt` until it succeeds, fails with a non-retryable error or runs out of
+    /// retries.
+    async fn retrying<T, Fut>(
+        &self,
+        what: &str,
+        mut attempt: impl FnMut() -> Fut,
+    ) -> Result<T, ClientError>
+    where
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut delay = self.retry.initial_backoff;
+        let mut retries = 0;
+        loop {
+            let e = match attempt().await {
+                Err(e) if is_retryable(&e) && retries < self.retry.max_retries => e,
+                result => return result,
+            };
+            retries += 1;
+            tracing::warn!(
+                "Medusa request {} failed ({}), retry {} in {:?}",
+                what,
+                e,
+                retries,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2).min(self.retry.max_backoff);
+        }
+    }
+}
+
+impl ClientT for MedusaClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), ClientError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        self.inner.notification(method, params).await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, ClientError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        if !IDEMPOTENT_METHODS.contains(&method) {
+            return self.inner.request(method, params).await;
+        }
+        let params = RawParams(params.to_rpc_params()?);
+        self.retrying(method, || self.inner.request(method, params.clone()))
+            .await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, ClientError>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        if !batch
+            .iter()
+            .all(|(method, _)| IDEMPOTENT_METHODS.contains(&method))
+        {
+            return self.inner.batch_request(batch).await;
+        }
+        self.retrying("batch", || self.inner.batch_request(batch.clone()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use alloy::primitives::{Address, U256};
+    use jsonrpsee::rpc_params;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::client::MedusaRpcClient;
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_idempotent_methods_exist() {
+        use crate::client::MedusaRpcServer;
+        use crate::mock::MockMedusa;
+        use crate::test_support::test_deployment;
+
+        let module = MockMedusa::new(test_deployment()).into_rpc();
+        let methods: Vec<&str> = module.method_names().collect();
+        for method in IDEMPOTENT_METHODS {
+            assert!(
+                methods.contains(method),
+                "{method} is not a MedusaRpc method"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_only_idempotent_methods() {
+        // A server that drops every connection, failing each attempt with a transport error.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                drop(stream);
+            }
+        });
+
+        let client = MedusaClientConfig::default()
+            .with_retry(RetryConfig {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            })
+            .build(format!("http://{addr}"))
+            .unwrap();
+
+        assert!(client.get_nonce(Address::ZERO).await.is_err());
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 3);
+        assert!(client.get_connected_solvers().await.is_err());
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 3);
+        let result: Result<(), _> = client.request(methods::PROPOSE_INTENT, rpc_params![]).await;
+        assert!(result.is_err());
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 1);
+
+        let mut batch = BatchRequestBuilder::new();
+        batch
+            .insert(methods::GET_NONCE, rpc_params![Address::ZERO])
+            .unwrap();
+        assert!(client.batch_request::<U256>(batch.clone()).await.is_err());
+        assert_eq!(attempts.swap(0, Ordering::SeqCst), 3);
+        batch
+            .insert(methods::PROPOSE_INTENT, rpc_params![])
+            .unwrap();
+        assert!(client.batch_request::<U256>(batch).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(all(
+        feature = "mock",
+        any(feature = "ws-native-tls", feature = "ws-rustls")
+    ))]
+    #[tokio::test]
+    async fn test_ws_rpc_client() {
+        use alloy::primitives::{B256, U256};
+
+        use crate::mock::MockMedusa;
+        use crate::test_support::test_deployment;
+
+        let (addr, _handle) = MockMedusa::new(test_deployment())
+            .start("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let client = create_medusa_ws_rpc_client(format!("ws://{addr}"), Default::default())
+            .await
+            .unwrap();
+        assert_eq!(client.get_nonce(Address::ZERO).await.unwrap(), U256::ZERO);
+        assert!(client.get_intent(B256::ZERO).await.unwrap().is_none());
+    }
+}
@@ -1,9 +1,12 @@
 use alloy::dyn_abi::TypedData;
 use alloy::primitives::{Address, B256, Bytes, U256};
-use anyhow::Result;
+#[cfg(feature = "server")]
+use jsonrpsee::core::RpcResult;
 pub use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::proc_macros::rpc;
 
+use super::medusa_client::MedusaClientConfig;
+use crate::error::MedusaError;
 use crate::types::intents::{Intent, IntentHistory, IntentId, IntentState, SignedIntent};
 use crate::types::refinement::RefinementStatus;
 use crate::types::rpc_payloads::{
@@ -13,8 +16,82 @@ use crate::types::rpc_payloads::{
 use crate::types::sol_types::{CrossChainIntent, FastWithdrawalPermit};
 use crate::types::solution::SignedSolution;
 
-#[rpc(client)]
+/// Names of the [`MedusaRpc`] methods, for raw and batched requests.
+///
+/// `#[method(name = ..)]` only takes literals, so these are checked against the methods the
+/// server registers in a test.
+pub mod methods {
+    pub const GET_VAULT_SHARE_PRICE_DATA: &str = "getVaultSharePriceData";
+    pub const GET_MTOKEN_BALANCE_IN_VAULT: &str = "getMTokenBalanceInVault";
+    pub const GET_DEPOSITOR_VAULT_SHARES: &str = "getDepositorVaultShares";
+    pub const GET_VAULT_TOTAL_ASSET_VALUE: &str = "getVaultTotalAssetValue";
+    pub const GET_VAULT_TOTAL_SHARES: &str = "getVaultTotalShares";
+    pub const PREVIEW_DEPOSIT_TO_VAULT: &str = "previewDepositToVault";
+    pub const DEPOSIT_TO_VAULT: &str = "depositToVault";
+    pub const PREVIEW_MAXIMUM_WITHDRAW_FROM_VAULT: &str = "previewMaximumWithdrawFromVault";
+    pub const WITHDRAW_FROM_VAULT: &str = "withdrawFromVault";
+    pub const GET_MTOKEN_BALANCE_BY_AUTHOR: &str = "getMtokenBalanceByAuthor";
+    pub const GET_SOLUTION: &str = "getSolution";
+    pub const GET_CONNECTED_SOLVERS: &str = "getConnectedSolvers";
+    pub const GET_INTENT_IDS_BY_AUTHOR: &str = "getIntentIdsByAuthor";
+    pub const GET_ACTIVE_INTENTS_BY_AUTHOR: &str = "getActiveIntentsByAuthor";
+    pub const GET_LIQUIDITY_INTENTS_BY_AUTHOR: &str = "getLiquidityIntentsByAuthor";
+    pub const GET_BRIDGE_INTENTS_BY_AUTHOR: &str = "getBridgeIntentsByAuthor";
+    pub const GET_LATEST_LIQUIDITY_VERSION: &str = "getLatestLiquidityVersion";
+    pub const GET_SOLUTIONS_FOR_SOLVER: &str = "getSolutionsForSolver";
+    pub const GET_INTENT: &str = "getIntent";
+    pub const GET_INTENT_STATUS: &str = "getIntentStatus";
+    pub const PROPOSE_INTENT: &str = "proposeIntent";
+    pub const CREATE_REFINEMENT: &str = "createRefinement";
+    pub const QUERY_REFINEMENT: &str = "queryRefinement";
+    pub const CANCEL_INTENT: &str = "cancelIntent";
+    pub const GET_HISTORY: &str = "getHistory";
+    pub const WITHDRAW_MTOKENS: &str = "withdrawMtokens";
+    pub const FAST_WITHDRAW_MTOKEN_WITH_WITNESS: &str = "fastWithdrawMTokenWithWitness";
+    pub const FAST_WITHDRAW_MTOKEN: &str = "fastWithdrawMToken";
+    pub const GET_FAILED_INTENTS_SINCE: &str = "getFailedIntentsSince";
+    pub const GET_NONCE: &str = "getNonce";
+    pub const REQUEST_ADD_SOLVER: &str = "requestAddSolver";
+    pub const PUBLISH_CROSS_CHAIN_INTENT: &str = "publishCrossChainIntent";
+
+    pub const ALL: &[&str] = &[
+        GET_VAULT_SHARE_PRICE_DATA,
+        GET_MTOKEN_BALANCE_IN_VAULT,
+        GET_DEPOSITOR_VAULT_SHARES,
+        GET_VAULT_TOTAL_ASSET_VALUE,
+        GET_VAULT_TOTAL_SHARES,
+        PREVIEW_DEPOSIT_TO_VAULT,
+        DEPOSIT_TO_VAULT,
+        PREVIEW_MAXIMUM_WITHDRAW_FROM_VAULT,
+        WITHDRAW_FROM_VAULT,
+        GET_MTOKEN_BALANCE_BY_AUTHOR,
+        GET_SOLUTION,
+        GET_CONNECTED_SOLVERS,
+        GET_INTENT_IDS_BY_AUTHOR,
+        GET_ACTIVE_INTENTS_BY_AUTHOR,
+        GET_LIQUIDITY_INTENTS_BY_AUTHOR,
+        GET_BRIDGE_INTENTS_BY_AUTHOR,
+        GET_LATEST_LIQUIDITY_VERSION,
+        GET_SOLUTIONS_FOR_SOLVER,
+        GET_INTENT,
+        GET_INTENT_STATUS,
+        PROPOSE_INTENT,
+        CREATE_REFINEMENT,
+        QUERY_REFINEMENT,
+        CANCEL_INTENT,
+        GET_HISTORY,
+        WITHDRAW_MTOKENS,
+        FAST_WITHDRAW_MTOKEN_WITH_WITNESS,
+        FAST_WITHDRAW_MTOKEN,
+        GET_FAILED_INTENTS_SINCE,
+        GET_NONCE,
+        REQUEST_ADD_SOLVER,
+        PUBLISH_CROSS_CHAIN_INTENT,
+    ];
+}
 
+#[cfg_attr(not(feature = "server"), rpc(client))]
+#[cfg_attr(feature = "server", rpc(client, server))]
 pub trait MedusaRpc {
     #[method(name = "getVaultSharePriceData")]
     async fn get_vault_share_price_data(&self, teller_address: Address) -> RpcResult<(U256, U256)>;
@@ -153,9 +230,29 @@ pub trait MedusaRpc {
     ) -> RpcResult<B256>;
 }
 
-pub fn create_medusa_rpc_client(url: String) -> Result<HttpClient> {
-    HttpClientBuilder::default()
-        .request_timeout(std::time::Duration::from_secs(5))
-        .build(url)
-        .map_err(anyhow::Error::msg)
+/// Connects to Medusa over HTTP with the default [`MedusaClientConfig`] limits and no retries.
+///
+/// Use [`create_medusa_rpc_client_with_config`](super::medusa_client::create_medusa_rpc_client_with_config)
+/// for custom settings and retries.
+pub fn create_medusa_rpc_client(url: String) -> Result<HttpClient, MedusaError> {
+    Ok(MedusaClientConfig::default()
+        .http_client_builder()
+        .build(url)?)
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::mock::MockMedusa;
+    use crate::test_support::test_deployment;
+
+    #[test]
+    fn test_method_names_match_registered_methods() {
+        let module = MockMedusa::new(test_deployment()).into_rpc();
+        let registered: HashSet<&str> = module.method_names().collect();
+        let named: HashSet<&str> = methods::ALL.iter().copied().collect();
+        assert_eq!(named, registered);
+    }
 }
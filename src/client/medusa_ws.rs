@@ -1,103 +1,611 @@
-use anyhow::Result;
-use futures::{SinkExt, StreamExt};
-use tokio::sync::mpsc;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use futures::future::BoxFuture;
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::task::JoinHandle;
-use tokio_tungstenite::connect_async;
-use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 
+use crate::error::{Error, MedusaError};
+use crate::types::intents::{IntentId, SignedIntent};
+use crate::types::refinement::RefinementStatus;
 use crate::types::rpc_payloads::SignedAddSolver;
-use crate::types::ws::{WsBroadcastMessage, WsPayload};
+use crate::types::solution::{SignedSolution, Solution};
+use crate::types::ws::{ComplementaryWithdrawal, WsBroadcastMessage, WsPayload};
 
-/// Connects to Medusa WebSocket and spawns a task to handle messages
-///
-/// Returns: (task_handle, broadcast_receiver, payload_sender, close_sender)
-///
-/// - `broadcast_receiver`: Receive messages from the WebSocket
-/// - `payload_sender`: Send payloads to the WebSocket (cloneable)
-/// - `close_sender`: Send () to close the connection (cloneable)
-/// - `task_handle`: JoinHandle for the background task
-pub async fn create_medusa_ws_client(
-    url: String,
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Produces a freshly signed `AddSolver` payload, e.g. with a new nonce.
+pub type AddSolverFactory =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<SignedAddSolver, Error>> + Send + Sync>;
+
+/// How the client authenticates as a solver every time it (re)connects.
+#[derive(Clone)]
+pub enum SolverAuth {
+    /// Re-send the same signed payload on every connection.
+    Static(SignedAddSolver),
+    /// Sign a new payload on every connection.
+    Factory(AddSolverFactory),
+}
+
+impl SolverAuth {
+    async fn payload(&self) -> Result<SignedAddSolver, Error> {
+        match self {
+            SolverAuth::Static(signed) => Ok(signed.clone()),
+            SolverAuth::Factory(factory) => factory().await,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Give up after this many consecutive failed attempts. `None` retries forever.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    Connected,
+    Reconnecting { attempt: u32, delay: Duration },
+    Disconnected,
+}
+
+/// Why the connection to Medusa ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CloseReason {
+    /// `shutdown` was called or the client was dropped.
+    Requested,
+    /// Medusa sent a close frame.
+    ClosedByServer(Option<CloseFrame>),
+    /// The socket failed or ended without a close frame.
+    ConnectionLost(String),
+}
+
+fn transport_error(err: WsError) -> MedusaError {
+    MedusaError::Transport(Box::new(err))
+}
+
+async fn connect_and_add_solver(
+    url: &str,
     signed_add_solver: SignedAddSolver,
-) -> Result<(
-    mpsc::Receiver<WsBroadcastMessage>,
-    mpsc::Sender<WsPayload>,
-    mpsc::Sender<()>,
-    JoinHandle<()>,
-)> {
-    let (mut ws_stream, _) = connect_async(url).await?;
+) -> Result<WsStream, Error> {
+    let (mut ws_stream, _) = connect_async(url).await.map_err(transport_error)?;
     ws_stream
         .send(Message::Text(
             serde_json::to_string(&WsPayload::AddSolver(signed_add_solver))?.into(),
         ))
-        .await?;
+        .await
+        .map_err(transport_error)?;
+    Ok(ws_stream)
+}
 
-    let (broadcast_send, broadcast_recv) = mpsc::channel(100);
-    let (payload_send, mut payload_recv) = mpsc::channel(100);
-    let (close_send, mut close_recv) = mpsc::channel(1);
+/// Why a request failed without an answer. Surfaced as [`MedusaError::Transport`].
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+enum QueryError {
+    #[error("WS connection is closed")]
+    Closed,
+    #[error("WS connection lost before the query was answered")]
+    Lost,
+    #[error("an earlier WS query was never answered, so answers can no longer be matched")]
+    Unanswered,
+    #[error("WS answers are out of sync with the queries sent")]
+    Desynced,
+}
 
-    let task_handle = tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                msg = ws_stream.next() => {
-                    match msg {
-                        Some(Ok(Message::Text(raw_message))) => {
-                            match serde_json::from_str(&raw_message) {
-                                Ok(message) => {
-                                    if let Err(e) = broadcast_send.send(message).await {
-                                        tracing::error!("Broadcast receiver dropped: {}, closing connection", e);
-                                        break;
+impl From<QueryError> for MedusaError {
+    fn from(err: QueryError) -> Self {
+        MedusaError::Transport(Box::new(err))
+    }
+}
+
+type Responder = oneshot::Sender<Result<Vec<Solution>, QueryError>>;
+
+/// A payload to send, optionally awaiting the `Solutions` broadcast that answers it.
+struct Request {
+    payload: WsPayload,
+    responder: Option<Responder>,
+    /// How long a query may wait for its answer.
+    timeout: Duration,
+}
+
+/// A query sent to Medusa and not answered yet.
+struct PendingQuery {
+    query: WsPayload,
+    deadline: Instant,
+    responder: Option<Responder>,
+}
+
+impl PendingQuery {
+    /// Whether `solutions` can be the answer to this query. Solutions carry no solver, so
+    /// only answers to intent queries can be checked.
+    fn matches(&self, solutions: &[Solution]) -> bool {
+        match &self.query {
+            WsPayload::GetSolutionsForIntent(intent_id) => solutions
+                .iter()
+                .all(|solution| solution.intent_ids.contains(intent_id)),
+            _ => true,
+        }
+    }
+
+    fn fail(self, error: QueryError) {
+        if let Some(responder) = self.responder {
+            let _ = responder.send(Err(error));
+        }
+    }
+}
+
+fn fail_all(pending: &mut VecDeque<PendingQuery>, error: QueryError) {
+    tracing::warn!("Failing {} pending WS queries: {}", pending.len(), error);
+    for query in pending.drain(..) {
+        query.fail(error);
+    }
+}
+
+/// Pumps messages between the socket and the channels until the session ends.
+///
+/// Medusa answers solution queries in the order it receives them, and the tag of a `Solutions`
+/// answer is a timestamp rather than an echo of the query, so `pending` holds one entry per
+/// unanswered query, oldest first. Queries sent without a responder still take a slot. Because
+/// a missing or late answer would shift every later answer onto the wrong query, the session
+/// is desynced as soon as the oldest query passes its deadline or an answer does not fit its
+/// query: all pending queries fail, and so does every later query until the next connection.
+async fn run_session(
+    ws_stream: &mut WsStream,
+    broadcast_send: &broadcast::Sender<WsBroadcastMessage>,
+    request_recv: &mut mpsc::Receiver<Request>,
+    close_recv: &mut mpsc::Receiver<()>,
+    pending: &mut VecDeque<PendingQuery>,
+) -> CloseReason {
+    let mut desynced = false;
+    loop {
+        let next_deadline = pending.front().map(|query| query.deadline);
+        tokio::select! {
+            _ = tokio::time::sleep_until(next_deadline.unwrap_or_else(Instant::now)),
+                if next_deadline.is_some() =>
+            {
+                fail_all(pending, QueryError::Unanswered);
+                desynced = true;
+            }
+            msg = ws_stream.next() => {
+                match msg {
+                    Some(Ok(Message::Text(raw_message))) => {
+                        match serde_json::from_str(&raw_message) {
+                            Ok(message) => {
+                                if let WsBroadcastMessage::Solutions(_, solutions) = &message
+                                    && let Some(query) = pending.pop_front()
+                                {
+                                    if query.matches(solutions) {
+                                        if let Some(responder) = query.responder {
+                                            // The requester may have timed out already.
+                                            let _ = responder.send(Ok(solutions.clone()));
+                                        }
+                                    } else {
+                                        query.fail(QueryError::Desynced);
+                                        fail_all(pending, QueryError::Desynced);
+                                        desynced = true;
                                     }
-                                },
-                                Err(e) => {
-                                    tracing::error!("Failed to parse WS message: {}", e);
-                                    continue;
                                 }
-                            };
-                        }
-                        Some(Ok(Message::Ping(ping))) => {
-                            if let Err(e) = ws_stream.send(Message::Pong(ping)).await {
-                                tracing::error!("Failed to send Pong message: {}", e);
-                                break;
+                                // Having no subscribers at the moment is not an error.
+                                let _ = broadcast_send.send(message);
+                            },
+                            Err(e) => {
+                                tracing::error!("Failed to parse WS message: {}", e);
+                                continue;
                             }
+                        };
+                    }
+                    Some(Ok(Message::Ping(ping))) => {
+                        if let Err(e) = ws_stream.send(Message::Pong(ping)).await {
+                            tracing::error!("Failed to send Pong message: {}", e);
+                            return CloseReason::ConnectionLost(e.to_string());
                         }
-                        Some(Ok(Message::Close(frame))) => {
-                            tracing::warn!("WS connection closed: {:?}", frame);
-                            break;
-                        }
-                        Some(Err(e)) => {
-                            tracing::error!("WebSocket error: {}", e);
-                            break;
-                        }
-                        None => {
-                            tracing::warn!("WS connection closed");
-                            break;
-                        }
-                        _ => {}
                     }
+                    Some(Ok(Message::Close(frame))) => {
+                        tracing::warn!("WS connection closed: {:?}", frame);
+                        return CloseReason::ClosedByServer(frame);
+                    }
+                    Some(Err(e)) => {
+                        tracing::error!("WebSocket error: {}", e);
+                        return CloseReason::ConnectionLost(e.to_string());
+                    }
+                    None => {
+                        tracing::warn!("WS connection closed");
+                        return CloseReason::ConnectionLost("stream ended".to_string());
+                    }
+                    _ => {}
                 }
-                Some(payload) = payload_recv.recv() => {
-                    match serde_json::to_string(&payload) {
-                        Ok(payload) => {
-                            if let Err(e) = ws_stream.send(Message::Text(payload.into())).await {
-                                tracing::error!("Failed to send WS payload to medusa: {}", e);
-                                continue;
-                            }
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to serialize payload: {}", e);
+            }
+            request = request_recv.recv() => {
+                // All client handles were dropped.
+                let Some(Request { payload, responder, timeout }) = request else {
+                    return CloseReason::Requested;
+                };
+                let is_query = matches!(
+                    payload,
+                    WsPayload::GetSolutionsForIntent(_) | WsPayload::GetSolutionsForSolver(_)
+                );
+                // Answers can no longer be matched, so awaited queries fail without being sent.
+                if is_query && desynced {
+                    if let Some(responder) = responder {
+                        let _ = responder.send(Err(QueryError::Desynced));
+                    }
+                    continue;
+                }
+                match serde_json::to_string(&payload) {
+                    Ok(raw_payload) => {
+                        if let Err(e) = ws_stream.send(Message::Text(raw_payload.into())).await {
+                            tracing::error!("Failed to send WS payload to medusa: {}", e);
                             continue;
                         }
+                        if is_query {
+                            pending.push_back(PendingQuery {
+                                query: payload,
+                                deadline: Instant::now() + timeout,
+                                responder,
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to serialize payload: {}", e);
+                        continue;
                     }
                 }
-                _ = close_recv.recv() => {
-                    break;
+            }
+            _ = close_recv.recv() => {
+                return CloseReason::Requested;
+            }
+        }
+    }
+}
+
+/// Reconnects with exponential backoff. Returns `None` if closing was requested meanwhile or the
+/// retry budget is exhausted.
+async fn reestablish(
+    url: &str,
+    auth: &SolverAuth,
+    config: &ReconnectConfig,
+    event_send: &broadcast::Sender<ConnectionEvent>,
+    close_recv: &mut mpsc::Receiver<()>,
+) -> Option<WsStream> {
+    let mut attempt = 0;
+    let mut delay = config.initial_backoff;
+    loop {
+        attempt += 1;
+        if config.max_retries.is_some_and(|max| attempt > max) {
+            tracing::error!(
+                "Giving up on WS reconnection after {} attempts",
+                attempt - 1
+            );
+            return None;
+        }
+        let _ = event_send.send(ConnectionEvent::Reconnecting { attempt, delay });
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = close_recv.recv() => return None,
+        }
+        delay = (delay * 2).min(config.max_backoff);
+
+        let connected = match auth.payload().await {
+            Ok(signed_add_solver) => connect_and_add_solver(url, signed_add_solver).await,
+            Err(e) => Err(e),
+        };
+        match connected {
+            Ok(mut stream) => {
+                let resync = serde_json::to_string(&WsPayload::RequestOpenIntents)
+                    .expect("unit variant serializes");
+                if let Err(e) = stream.send(Message::Text(resync.into())).await {
+                    tracing::warn!("Failed to request open intents after reconnect: {}", e);
+                    continue;
                 }
+                tracing::info!("WS connection re-established");
+                return Some(stream);
+            }
+            Err(e) => {
+                tracing::warn!("WS reconnection attempt {} failed: {}", attempt, e);
+            }
+        }
+    }
+}
 
+/// A connection to the Medusa WebSocket API, authenticated as a solver.
+///
+/// Broadcasts can be consumed by any number of subscribers. Dropping the client closes the
+/// connection.
+pub struct MedusaWsClient {
+    request_send: mpsc::Sender<Request>,
+    request_timeout: Duration,
+    close_send: mpsc::Sender<()>,
+    broadcast_send: broadcast::WeakSender<WsBroadcastMessage>,
+    /// Subscribed before the reader starts, so the first subscriber misses nothing.
+    first_broadcast: Mutex<Option<broadcast::Receiver<WsBroadcastMessage>>>,
+    event_send: broadcast::WeakSender<ConnectionEvent>,
+    task_handle: JoinHandle<CloseReason>,
+}
+
+impl MedusaWsClient {
+    /// Connects and spawns the task driving the connection. If `reconnect` is set, dropped
+    /// connections are re-established, the solver is re-added using `auth` and
+    /// `RequestOpenIntents` is sent so subscribers can resync. The first connection attempt is
+    /// not retried.
+    pub async fn connect(
+        url: String,
+        auth: SolverAuth,
+        reconnect: Option<ReconnectConfig>,
+    ) -> Result<Self, Error> {
+        let mut ws_stream = connect_and_add_solver(&url, auth.payload().await?).await?;
+
+        let (broadcast_send, _) = broadcast::channel(1024);
+        let (event_send, _) = broadcast::channel(16);
+        let (request_send, mut request_recv) = mpsc::channel(100);
+        let (close_send, mut close_recv) = mpsc::channel(1);
+
+        // The client only keeps weak senders so that subscriber streams end with the task.
+        let weak_broadcast_send = broadcast_send.downgrade();
+        let weak_event_send = event_send.downgrade();
+        let first_broadcast = broadcast_send.subscribe();
+        let task_handle = tokio::spawn(async move {
+            let _ = event_send.send(ConnectionEvent::Connected);
+            let reason = loop {
+                let mut pending = VecDeque::new();
+                let reason = run_session(
+                    &mut ws_stream,
+                    &broadcast_send,
+                    &mut request_recv,
+                    &mut close_recv,
+                    &mut pending,
+                )
+                .await;
+                // Queries still pending now will never be answered; dropping their responders
+                // fails them.
+                drop(pending);
+                let _ = ws_stream.close(None).await;
+                let Some(config) = reconnect.as_ref() else {
+                    break reason;
+                };
+                if reason == CloseReason::Requested {
+                    break reason;
+                }
+                match reestablish(&url, &auth, config, &event_send, &mut close_recv).await {
+                    Some(stream) => ws_stream = stream,
+                    None => break reason,
+                }
+                let _ = event_send.send(ConnectionEvent::Connected);
+            };
+            let _ = event_send.send(ConnectionEvent::Disconnected);
+            tracing::info!("WS connection closed");
+            reason
+        });
+
+        Ok(Self {
+            request_send,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            close_send,
+            broadcast_send: weak_broadcast_send,
+            first_broadcast: Mutex::new(Some(first_broadcast)),
+            event_send: weak_event_send,
+            task_handle,
+        })
+    }
+
+    /// Returns a stream of broadcasts. The first call sees every broadcast since the client
+    /// connected, later calls only see broadcasts from then on. The stream ends when the
+    /// connection does.
+    ///
+    /// Subscribers that fall too far behind skip the oldest messages. The client then requests
+    /// the open intents again, so the subscriber can resync from the `ExistingOpenIntents`
+    /// answer.
+    pub fn subscribe(&self) -> impl Stream<Item = WsBroadcastMessage> + Send + 'static {
+        let recv = self
+            .first_broadcast
+            .lock()
+            .unwrap()
+            .take()
+            .or_else(|| self.broadcast_send.upgrade().map(|send| send.subscribe()));
+        let Some(recv) = recv else {
+            return futures::stream::empty().right_stream();
+        };
+        let request_send = self.request_send.downgrade();
+        let resync = move |skipped| {
+            tracing::warn!(
+                "WS subscriber lagged, skipped {} messages; requesting open intents",
+                skipped
+            );
+            if let Some(request_send) = request_send.upgrade() {
+                let _ = request_send.try_send(Request {
+                    payload: WsPayload::RequestOpenIntents,
+                    responder: None,
+                    timeout: DEFAULT_REQUEST_TIMEOUT,
+                });
             }
+        };
+        lagging_broadcast_stream(recv, resync).left_stream()
+    }
+
+    /// Returns a stream of connection state changes from now on. The stream ends when the
+    /// connection does.
+    pub fn connection_events(&self) -> impl Stream<Item = ConnectionEvent> + Send + 'static {
+        weak_broadcast_stream(&self.event_send)
+    }
+
+    /// Sets how long the `get_solutions_*` queries wait for their answer.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub async fn send(&self, payload: WsPayload) -> Result<(), MedusaError> {
+        self.request_send
+            .send(Request {
+                payload,
+                responder: None,
+                timeout: self.request_timeout,
+            })
+            .await
+            .map_err(|_| QueryError::Closed.into())
+    }
+
+    async fn query_solutions(&self, payload: WsPayload) -> Result<Vec<Solution>, MedusaError> {
+        let (responder, response) = oneshot::channel();
+        self.request_send
+            .send(Request {
+                payload,
+                responder: Some(responder),
+                timeout: self.request_timeout,
+            })
+            .await
+            .map_err(|_| QueryError::Closed)?;
+        match tokio::time::timeout(self.request_timeout, response).await {
+            Ok(Ok(Ok(solutions))) => Ok(solutions),
+            Ok(Ok(Err(e))) => Err(e.into()),
+            Ok(Err(_)) => Err(QueryError::Lost.into()),
+            Err(_) => Err(MedusaError::Timeout),
         }
-        let _ = ws_stream.close(None).await;
-        tracing::info!("WS connection closed");
-    });
-    Ok((broadcast_recv, payload_send, close_send, task_handle))
+    }
+
+    /// Fetches the solutions proposed for an intent.
+    pub async fn get_solutions_for_intent(
+        &self,
+        intent_id: IntentId,
+    ) -> Result<Vec<Solution>, MedusaError> {
+        self.query_solutions(WsPayload::GetSolutionsForIntent(intent_id))
+            .await
+    }
+
+    /// Fetches the solutions proposed by a solver.
+    pub async fn get_solutions_for_solver(
+        &self,
+        solver: Address,
+    ) -> Result<Vec<Solution>, MedusaError> {
+        self.query_solutions(WsPayload::GetSolutionsForSolver(solver))
+            .await
+    }
+
+    pub async fn propose_solution(
+        &self,
+        solution: SignedSolution,
+        intents: Vec<SignedIntent>,
+        complementary_withdrawal: bool,
+    ) -> Result<(), MedusaError> {
+        self.send(WsPayload::ProposeSolution(
+            solution,
+            intents,
+            ComplementaryWithdrawal(complementary_withdrawal),
+        ))
+        .await
+    }
+
+    pub async fn request_open_intents(&self) -> Result<(), MedusaError> {
+        self.send(WsPayload::RequestOpenIntents).await
+    }
+
+    pub async fn send_refinement(
+        &self,
+        intent_id: IntentId,
+        refinement: RefinementStatus,
+    ) -> Result<(), MedusaError> {
+        self.send(WsPayload::IntentRefinement(intent_id, refinement))
+            .await
+    }
+
+    /// Asks for the solutions of an intent. The answer arrives as a `Solutions` broadcast; use
+    /// [`Self::get_solutions_for_intent`] to await it instead.
+    pub async fn solutions_for_intent(&self, intent_id: IntentId) -> Result<(), MedusaError> {
+        self.send(WsPayload::GetSolutionsForIntent(intent_id)).await
+    }
+
+    /// Asks for the solutions of a solver. The answer arrives as a `Solutions` broadcast; use
+    /// [`Self::get_solutions_for_solver`] to await it instead.
+    pub async fn solutions_for_solver(&self, solver: Address) -> Result<(), MedusaError> {
+        self.send(WsPayload::GetSolutionsForSolver(solver)).await
+    }
+
+    /// Returns true once the connection has ended for good.
+    pub fn is_closed(&self) -> bool {
+        self.task_handle.is_finished()
+    }
+
+    /// Closes the connection and waits for the background task to finish.
+    ///
+    /// If the connection had already ended, returns why it did.
+    pub async fn shutdown(self) -> CloseReason {
+        let _ = self.close_send.try_send(());
+        match self.task_handle.await {
+            Ok(reason) => reason,
+            Err(e) => CloseReason::ConnectionLost(e.to_string()),
+        }
+    }
+}
+
+pub(crate) fn broadcast_stream<T: Clone + Send + 'static>(
+    recv: broadcast::Receiver<T>,
+) -> impl Stream<Item = T> + Send + 'static {
+    lagging_broadcast_stream(recv, |skipped| {
+        tracing::warn!("Subscriber lagged, skipped {} messages", skipped);
+    })
+}
+
+/// Like [`broadcast_stream`], calling `on_lag` with the number of skipped messages whenever the
+/// receiver falls behind.
+fn lagging_broadcast_stream<T: Clone + Send + 'static>(
+    recv: broadcast::Receiver<T>,
+    on_lag: impl Fn(u64) + Send + 'static,
+) -> impl Stream<Item = T> + Send + 'static {
+    futures::stream::unfold((recv, on_lag), |(mut recv, on_lag)| async move {
+        loop {
+            match recv.recv().await {
+                Ok(item) => return Some((item, (recv, on_lag))),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => on_lag(skipped),
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+fn weak_broadcast_stream<T: Clone + Send + 'static>(
+    send: &broadcast::WeakSender<T>,
+) -> impl Stream<Item = T> + Send + 'static {
+    match send.upgrade() {
+        Some(send) => broadcast_stream(send.subscribe()).left_stream(),
+        None => futures::stream::empty().right_stream(),
+    }
+}
+
+/// Connects to Medusa WebSocket as a solver, without reconnection.
+pub async fn create_medusa_ws_client(
+    url: String,
+    signed_add_solver: SignedAddSolver,
+) -> Result<MedusaWsClient, Error> {
+    MedusaWsClient::connect(url, SolverAuth::Static(signed_add_solver), None).await
+}
+
+/// Connects to Medusa WebSocket as a solver and keeps the connection alive across drops.
+///
+/// See [`MedusaWsClient::connect`].
+pub async fn create_reconnecting_medusa_ws_client(
+    url: String,
+    auth: SolverAuth,
+    config: ReconnectConfig,
+) -> Result<MedusaWsClient, Error> {
+    MedusaWsClient::connect(url, auth, Some(config)).await
 }
@@ -1,9 +1,27 @@
 mod spoke;
 
+pub use intent_lineage::{IntentLineage, fetch_intent_lineage};
+pub use intent_tracker::IntentTracker;
+pub use medusa_batch::{get_intent_statuses, get_intents, get_solutions_for_intents};
+#[cfg(any(feature = "ws-native-tls", feature = "ws-rustls"))]
+pub use medusa_client::create_medusa_ws_rpc_client;
+pub use medusa_client::{
+    MedusaClient, MedusaClientConfig, RetryConfig, create_medusa_rpc_client_with_config,
+};
 pub use medusa_rpc::MedusaRpcClient;
+#[cfg(feature = "server")]
+pub use medusa_rpc::MedusaRpcServer;
 pub use medusa_rpc::create_medusa_rpc_client;
-pub use medusa_ws::create_medusa_ws_client;
+pub use medusa_ws::{
+    MedusaWsClient, create_medusa_ws_client, create_reconnecting_medusa_ws_client,
+};
+pub use open_intent_book::OpenIntentBook;
 pub use spoke::{EthereumProvider, SpokeClient};
 
+pub mod intent_lineage;
+pub mod intent_tracker;
+pub mod medusa_batch;
+pub mod medusa_client;
 pub mod medusa_rpc;
 pub mod medusa_ws;
+pub mod open_intent_book;
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use alloy::primitives::{Address, U256};
+use futures::{Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use super::medusa_ws::broadcast_stream;
+use crate::types::intents::{FillStructure, Intent, IntentId, IntentState, current_timestamp_sec};
+use crate::types::ws::WsBroadcastMessage;
+
+/// Why an intent left the book.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemovalReason {
+    StatusChanged(IntentState),
+    Solved(Address),
+    Expired,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookChange {
+    Added(Intent),
+    Removed(IntentId, RemovalReason),
+    /// The book was replaced by an `ExistingOpenIntents` snapshot.
+    Resynced,
+    /// Medusa asks solvers to refine this intent. It is not added to the book.
+    RefinementNeeded(Intent),
+}
+
+/// An in-memory view of the open intents, maintained from Medusa WS broadcasts.
+///
+/// Cloning is cheap and all clones share the same book.
+#[derive(Clone)]
+pub struct OpenIntentBook {
+    intents: Arc<RwLock<HashMap<IntentId, Intent>>>,
+    changes: broadcast::Sender<BookChange>,
+}
+
+impl Default for OpenIntentBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenIntentBook {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(1024);
+        Self {
+            intents: Default::default(),
+            changes,
+        }
+    }
+
+    /// Updates the book from a single broadcast.
+    pub fn apply(&self, message: &WsBroadcastMessage) {
+        self.apply_at(message, current_timestamp_sec());
+    }
+
+    fn apply_at(&self, message: &WsBroadcastMessage, now: u64) {
+        let mut changes = Vec::new();
+        {
+            let mut intents = self.intents.write().unwrap();
+            match message {
+                WsBroadcastMessage::ExistingOpenIntents(snapshot) => {
+                    *intents = snapshot
+                        .iter()
+                        .filter(|intent| !is_expired(intent, now))
+                        .map(|intent| (intent.intent_id(), intent.clone()))
+                        .collect();
+                    changes.push(BookChange::Resynced);
+                }
+                WsBroadcastMessage::NewIntent(intent) => {
+                    if !is_expired(intent, now) {
+                        intents.insert(intent.intent_id(), intent.clone());
+                        changes.push(BookChange::Added(intent.clone()));
+                    }
+                }
+                WsBroadcastMessage::IntentStatusUpdated(intent_id, state) => {
+                    if *state != IntentState::Open && intents.remove(intent_id).is_some() {
+                        changes.push(BookChange::Removed(
+                            *intent_id,
+                            RemovalReason::StatusChanged(state.clone()),
+                        ));
+                    }
+                }
+                WsBroadcastMessage::IntentsSolved(intent_ids, solver) => {
+                    for intent_id in intent_ids {
+                        if intents.remove(intent_id).is_some() {
+                            changes.push(BookChange::Removed(
+                                *intent_id,
+                                RemovalReason::Solved(*solver),
+                            ));
+                        }
+                    }
+                }
+                WsBroadcastMessage::RefinementNeededForIntent(intent) => {
+                    changes.push(BookChange::RefinementNeeded(intent.clone()));
+                }
+                WsBroadcastMessage::Solutions(..) | WsBroadcastMessage::SolutionRejected(_) => {}
+            }
+        }
+        for change in changes {
+            let _ = self.changes.send(change);
+        }
+    }
+
+    /// Removes every intent whose `valid_before` is not after `now` (unix seconds).
+    pub fn prune_expired(&self, now: u64) {
+        let expired: Vec<IntentId> = {
+            let mut intents = self.intents.write().unwrap();
+            let expired = intents
+                .iter()
+                .filter(|(_, intent)| is_expired(intent, now))
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            for id in &expired {
+                intents.remove(id);
+            }
+            expired
+        };
+        for id in expired {
+            let _ = self
+                .changes
+                .send(BookChange::Removed(id, RemovalReason::Expired));
+        }
+    }
+
+    /// Spawns a task that applies every broadcast from `messages` and prunes expired intents
+    /// every `prune_interval`. The task ends when the stream does.
+    pub fn track(
+        &self,
+        messages: impl Stream<Item = WsBroadcastMessage> + Send + 'static,
+        prune_interval: Duration,
+    ) -> JoinHandle<()> {
+        let book = self.clone();
+        tokio::spawn(async move {
+            let mut messages = std::pin::pin!(messages);
+            let mut prune = tokio::time::interval(prune_interval);
+            loop {
+                tokio::select! {
+                    message = messages.next() => match message {
+                        Some(message) => book.apply(&message),
+                        None => break,
+                    },
+                    _ = prune.tick() => book.prune_expired(current_timestamp_sec()),
+                }
+            }
+        })
+    }
+
+    /// Returns a stream of every change to the book from now on.
+    pub fn changes(&self) -> impl Stream<Item = BookChange> + Send + 'static {
+        broadcast_stream(self.changes.subscribe())
+    }
+
+    pub fn get(&self, intent_id: &IntentId) -> Option<Intent> {
+        self.intents.read().unwrap().get(intent_id).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.intents.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn intents(&self) -> Vec<Intent> {
+        self.filter(|_| true)
+    }
+
+    pub fn by_src_m_token(&self, m_token: Address) -> Vec<Intent> {
+        self.filter(|intent| intent.src_m_token == m_token)
+    }
+
+    pub fn by_outcome_m_token(&self, m_token: Address) -> Vec<Intent> {
+        self.filter(|intent| intent.outcome.m_tokens.contains(&m_token))
+    }
+
+    pub fn by_author(&self, author: Address) -> Vec<Intent> {
+        self.filter(|intent| intent.author == author)
+    }
+
+    pub fn by_fill_structure(&self, fill_structure: &FillStructure) -> Vec<Intent> {
+        self.filter(|intent| intent.outcome.fill_structure == *fill_structure)
+    }
+
+    pub fn filter(&self, predicate: impl Fn(&Intent) -> bool) -> Vec<Intent> {
+        self.intents
+            .read()
+            .unwrap()
+            .values()
+            .filter(|intent| predicate(intent))
+            .cloned()
+            .collect()
+    }
+}
+
+fn is_expired(intent: &Intent, now: u64) -> bool {
+    intent.valid_before <= U256::from(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(author: u8, valid_before: u64) -> Intent {
+        Intent::simple_swap(
+            Address::repeat_byte(author),
+            U256::from(valid_before),
+            U256::ZERO,
+            None,
+            Address::repeat_byte(0xaa),
+            U256::from(100),
+            Address::repeat_byte(0xbb),
+            U256::from(1),
+        )
+    }
+
+    #[test]
+    fn test_book_follows_broadcasts() {
+        let book = OpenIntentBook::new();
+        let (a, b, c) = (intent(1, 2_000), intent(2, 2_000), intent(3, 1_500));
+        book.apply_at(
+            &WsBroadcastMessage::ExistingOpenIntents(vec![a.clone(), b.clone()]),
+            1_000,
+        );
+        book.apply_at(&WsBroadcastMessage::NewIntent(c.clone()), 1_000);
+        assert_eq!(book.len(), 3);
+        assert_eq!(book.by_author(Address::repeat_byte(2)), vec![b.clone()]);
+        assert_eq!(book.by_outcome_m_token(Address::repeat_byte(0xbb)).len(), 3);
+
+        book.apply_at(
+            &WsBroadcastMessage::IntentsSolved(vec![a.intent_id()], Address::ZERO),
+            1_000,
+        );
+        book.apply_at(
+            &WsBroadcastMessage::IntentStatusUpdated(b.intent_id(), IntentState::Cancelled),
+            1_000,
+        );
+        assert_eq!(book.intents(), vec![c.clone()]);
+
+        book.prune_expired(1_500);
+        assert!(book.is_empty());
+    }
+}
@@ -7,8 +7,6 @@ use alloy::providers::{Identity, ProviderBuilder, RootProvider};
 use alloy::providers::{Provider, WalletProvider};
 use alloy::signers::Signature;
 
-use anyhow::Result;
-
 use crate::error::Error;
 use crate::types::sol_types::AssetReserves::AssetReservesInstance;
 use crate::types::sol_types::ERC20::ERC20Instance;
@@ -101,7 +99,7 @@ impl SpokeClient {
         Ok(receipt.transaction_hash)
     }
 
-    pub async fn get_erc20_balance(&self, owner: Address, token: Address) -> Result<U256> {
+    pub async fn get_erc20_balance(&self, owner: Address, token: Address) -> Result<U256, Error> {
         let erc20_contract = ERC20Instance::new(token, self.provider.clone());
         let balance = erc20_contract.balanceOf(owner).call().await?;
         Ok(balance)
@@ -112,7 +110,7 @@ impl SpokeClient {
         token: Address,
         spender: Address,
         amount: U256,
-    ) -> Result<B256> {
+    ) -> Result<B256, Error> {
         let erc20_contract = ERC20Instance::new(token, self.provider.clone());
         let receipt = erc20_contract
             .approve(spender, amount)
@@ -123,7 +121,7 @@ impl SpokeClient {
         Ok(receipt.transaction_hash)
     }
 
-    pub async fn get_native_token_balance(&self, owner: Address) -> Result<U256> {
+    pub async fn get_native_token_balance(&self, owner: Address) -> Result<U256, Error> {
         let balance = self.provider.get_balance(owner).await?;
         Ok(balance)
     }
@@ -0,0 +1,87 @@
+use jsonrpsee::core::ClientError;
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use thiserror::Error;
+
+use super::decode_rpc_revert;
+use crate::types::sol_types::All::AllErrors;
+
+/// Why a Medusa JSON-RPC call failed.
+#[derive(Error, Debug)]
+pub enum MedusaError {
+    /// The request did not reach Medusa or the connection was lost.
+    #[error("Medusa transport error: {0}")]
+    Transport(#[source] jsonrpsee::core::BoxError),
+    #[error("Medusa request timed out")]
+    Timeout,
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+    /// Medusa answered with an error that is not a contract revert.
+    #[error("Medusa rejected the request ({code}): {message}")]
+    Rejected { code: i32, message: String },
+    #[error("Execution reverted: {0}")]
+    Revert(AllErrors),
+    /// The client could not be set up, e.g. because of an invalid header.
+    #[error("Invalid Medusa client configuration: {0}")]
+    Config(String),
+    /// Any other client-side failure, e.g. a response that could not be parsed.
+    #[error(transparent)]
+    Client(ClientError),
+}
+
+impl From<ClientError> for MedusaError {
+    fn from(err: ClientError) -> Self {
+        if let Some(revert) = decode_rpc_revert(&err) {
+            return MedusaError::Revert(revert);
+        }
+        match err {
+            ClientError::Call(err) if err.code() == INVALID_PARAMS_CODE => {
+                MedusaError::InvalidParams(err.message().to_string())
+            }
+            ClientError::Call(err) => MedusaError::Rejected {
+                code: err.code(),
+                message: err.message().to_string(),
+            },
+            ClientError::Transport(err) => MedusaError::Transport(err),
+            err @ ClientError::RestartNeeded(_) => MedusaError::Transport(Box::new(err)),
+            ClientError::RequestTimeout => MedusaError::Timeout,
+            err => MedusaError::Client(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::sol_types::SolError;
+    use jsonrpsee::types::ErrorObject;
+
+    use super::*;
+    use crate::types::sol_types::All;
+
+    #[test]
+    fn test_maps_call_errors() {
+        let invalid = ClientError::Call(ErrorObject::owned(
+            INVALID_PARAMS_CODE,
+            "bad nonce",
+            None::<()>,
+        ));
+        assert!(matches!(
+            MedusaError::from(invalid),
+            MedusaError::InvalidParams(message) if message == "bad nonce"
+        ));
+
+        let revert = All::IntentBook__UnauthorizedCancellationAttempt {}.abi_encode();
+        let reverted = ClientError::Call(ErrorObject::owned(
+            3,
+            "execution reverted",
+            Some(alloy::primitives::Bytes::from(revert)),
+        ));
+        assert!(matches!(
+            MedusaError::from(reverted),
+            MedusaError::Revert(AllErrors::IntentBook__UnauthorizedCancellationAttempt(_))
+        ));
+        assert!(matches!(
+            MedusaError::from(ClientError::RequestTimeout),
+            MedusaError::Timeout
+        ));
+    }
+}
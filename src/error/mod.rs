@@ -1,6 +1,16 @@
+mod medusa;
+mod revert;
+
 use alloy::{primitives::U256, signers::Error as SignerError};
 use thiserror::Error;
 
+use crate::types::intents::{IntentId, IntentState};
+use crate::types::sol_types::All::AllErrors;
+use crate::types::sol_types::IntentState as SolIntentState;
+
+pub use medusa::MedusaError;
+pub use revert::{decode_revert, decode_rpc_revert};
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -8,9 +18,81 @@ pub enum Error {
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
     #[error(transparent)]
-    ContractError(#[from] alloy::contract::Error),
+    ContractError(alloy::contract::Error),
+    #[error("Execution reverted: {0}")]
+    Revert(AllErrors),
+    #[error(transparent)]
+    TransportError(#[from] alloy::transports::TransportError),
     #[error(transparent)]
     PendingTransactionError(#[from] alloy::providers::PendingTransactionError),
     #[error("Insufficient allowance {0}, needed {1}")]
     InsufficientAllowance(U256, U256),
+    #[error(transparent)]
+    Medusa(MedusaError),
+    #[error("timed out after {0:?}")]
+    Timeout(std::time::Duration),
+}
+
+impl From<MedusaError> for Error {
+    fn from(err: MedusaError) -> Self {
+        match err {
+            MedusaError::Revert(revert) => Error::Revert(revert),
+            err => Error::Medusa(err),
+        }
+    }
+}
+
+impl From<jsonrpsee::core::ClientError> for Error {
+    fn from(err: jsonrpsee::core::ClientError) -> Self {
+        MedusaError::from(err).into()
+    }
+}
+
+impl From<alloy::contract::Error> for Error {
+    fn from(err: alloy::contract::Error) -> Self {
+        match err.try_decode_into_interface_error::<AllErrors>() {
+            Ok(revert) => Error::Revert(revert),
+            Err(err) => Error::ContractError(err),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CancelIntentError {
+    #[error("intent {0} is not open")]
+    IntentNotOpen(IntentId),
+    #[error("cannot cancel intent in state {0:?}")]
+    CannotCancelNonOpenIntent(SolIntentState),
+    #[error("signer is not the author of the intent")]
+    UnauthorizedCancellationAttempt,
+    #[error("intent ended up {0:?} instead of cancelled")]
+    NotCancelled(IntentState),
+    #[error("intent was not cancelled within {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("the signer's nonce cannot be incremented")]
+    NonceOverflow,
+    #[error(transparent)]
+    SignerError(#[from] SignerError),
+    #[error("Execution reverted: {0}")]
+    Revert(AllErrors),
+    #[error(transparent)]
+    RpcError(MedusaError),
+}
+
+impl From<jsonrpsee::core::ClientError> for CancelIntentError {
+    fn from(err: jsonrpsee::core::ClientError) -> Self {
+        match MedusaError::from(err) {
+            MedusaError::Revert(AllErrors::IntentValidator__CancelError__IntentNotOpen(e)) => {
+                CancelIntentError::IntentNotOpen(e.intentId)
+            }
+            MedusaError::Revert(AllErrors::IntentBook__CannotCancelNonOpenIntent(e)) => {
+                CancelIntentError::CannotCancelNonOpenIntent(e.intentState)
+            }
+            MedusaError::Revert(AllErrors::IntentBook__UnauthorizedCancellationAttempt(_)) => {
+                CancelIntentError::UnauthorizedCancellationAttempt
+            }
+            MedusaError::Revert(revert) => CancelIntentError::Revert(revert),
+            err => CancelIntentError::RpcError(err),
+        }
+    }
 }
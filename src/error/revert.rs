@@ -0,0 +1,239 @@
+use std::fmt;
+
+use alloy::primitives::Bytes;
+use alloy::sol_types::SolInterface;
+
+use crate::types::sol_types::All::AllErrors;
+
+/// Decodes revert data returned by any Arcadia contract, e.g. the `data` field of a Medusa
+/// JSON-RPC error payload.
+///
+/// Returns `None` if the selector does not belong to a known Arcadia error.
+pub fn decode_revert(data: &Bytes) -> Option<AllErrors> {
+    AllErrors::abi_decode(data).ok()
+}
+
+/// Decodes the revert carried in the `data` field of a Medusa JSON-RPC error, if any.
+pub fn decode_rpc_revert(err: &jsonrpsee::core::ClientError) -> Option<AllErrors> {
+    let jsonrpsee::core::ClientError::Call(err) = err else {
+        return None;
+    };
+    let data: Bytes = serde_json::from_str(err.data()?.get()).ok()?;
+    decode_revert(&data)
+}
+
+/// Implements `Display` as `Name { field: value, .. }`, or just `Name` for errors without
+/// fields. Listing every error keeps the match exhaustive, so adding an error to `All` without
+/// listing it here fails to compile.
+macro_rules! impl_display {
+    ($($name:ident { $($field:ident),* }),* $(,)?) => {
+        impl fmt::Display for AllErrors {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $(AllErrors::$name(_err) => {
+                        f.write_str(stringify!($name))?;
+                        write_fields(f, &[$((stringify!($field), &_err.$field as &dyn fmt::Debug)),*])
+                    })*
+                }
+            }
+        }
+    };
+}
+
+fn write_fields(f: &mut fmt::Formatter<'_>, fields: &[(&str, &dyn fmt::Debug)]) -> fmt::Result {
+    for (i, (name, value)) in fields.iter().enumerate() {
+        let separator = if i == 0 { " { " } else { ", " };
+        write!(f, "{separator}{name}: {value:?}")?;
+    }
+    if !fields.is_empty() {
+        f.write_str(" }")?;
+    }
+    Ok(())
+}
+
+impl_display! {
+    IntentValidator__PublishError__ValidAfterLargerThanValidBefore {},
+    IntentValidator__PublishError__InvalidIntentNonce {},
+    IntentValidator__PublishError__IntentExpired {},
+    IntentValidator__PublishError__MissingField { intentId },
+    IntentValidator__PublishError__MAmountsMustYieldManageableOutcomes {},
+    IntentValidator__PublishError__IntentAlreadyExists { intentId },
+    IntentValidator__PublishError__OutcomeMTokenAndMAmountLengthMismatch { intentId },
+    IntentValidator__PublishError__UnsupportedIntentType { intentId },
+    IntentValidator__PublishError__ZeroOutcomeToken { intentId, index },
+    IntentValidator__PublishError__ZeroOutcomeAmount { intentId, index },
+    IntentValidator__CancelError__IntentNotOpen { intentId },
+    SolutionValidator__IntentNotOpen { intentId },
+    SolutionValidator__IntentExpired { intentId },
+    SolutionValidator__IntentValidAfterNotReached { intentId },
+    SolutionValidator__EmptySolution {},
+    SolutionValidator__SolutionMustHaveReceiptOutputs {},
+    SolutionValidator__ChildIntentAuthorMismatch { intentIdx },
+    SolutionValidator__ChildIntentMTokenMismatch { intentIdx },
+    SolutionValidator__ChildIntentValidBeforeMismatch { intentIdx },
+    SolutionValidator__ChildIntentValidAfterMismatch { intentIdx },
+    SolutionValidator__ChildIntentInvalidNonce { intentIdx },
+    SolutionValidator__ChildIntentOutcomeMismatch { intentIdx },
+    SolutionValidator__DuplicateIntentId { intentId },
+    SolutionValidator__UnfilledInputIntent { intentIdx },
+    SolutionValidator__DuplicateOutputIntentNonce { intentIdx1, intentIdx2 },
+    SolutionValidator__OrphanIntentOutput { intentIdx },
+    SolutionValidator__OrphanReceiptOutput { receiptIdx },
+    SolutionValidator__MoveRecordSrcIdxOutOfBounds { moveIdx },
+    SolutionValidator__FillRecordInIdxOutOfBounds { fillIdx },
+    SolutionValidator__IntentSpentAmountMismatch { intentIdx, moveIdx },
+    SolutionValidator__ReceiptMTokenMismatch { intentIdx },
+    SolutionValidator__InvalidMoveRecord { intentIdx, moveIdx },
+    SolutionValidator__ReceiptMTokenNotFoundInIntentOutcome { intentIdx, receiptIdx },
+    SolutionValidator__IntentTokenBurnt { intentIdx },
+    SolutionValidator__IntentTokenDoubleSpent { intentIdx },
+    SolutionValidator__IntentFillError__MultipleMTokensFilledForAnySingleIntent { intentIdx },
+    SolutionValidator__IntentFillError__NoMTokensFilledForAnySingleIntent { intentIdx },
+    SolutionValidator__MoveRecordOutputIdxOutOfBounds { moveIdx },
+    SolutionValidator__FillRecordOutputIdxOutOfBounds { fillIdx },
+    SolutionValidator__IntentMustSpendMToken { intentIdx },
+    SolutionValidator__PercentageIntentNotSatisfied { intentIdx, amtSpent, expectedSpentAmt },
+    SolutionValidator__ExactIntentMustSpendAllMTokens { intentIdx },
+    SolutionValidator__ExactIntentNotSatisfied { intentIdx },
+    SolutionValidator__UnsupportedFillStructure { intentIdx },
+    IntentBook__ValidAfterLargerThanValidBefore {},
+    IntentBook__InvalidIntentNonce {},
+    IntentBook__IntentExpired {},
+    IntentBook__IntentNonactivated {},
+    IntentBook__IntentAlreadyExists { _intentId },
+    IntentBook__UnauthorizedIntentPublisher {},
+    IntentBook__CannotLockIntentThatIsNotOpen { intentId },
+    IntentBook__CannotCancelNonOpenIntent { intentState },
+    IntentBook__UnauthorizedCancellationAttempt {},
+    IntentBook__InvalidSignature {},
+    IntentBook__InvalidIntentAuthor {},
+    IntentBook__IntentNotSpendable { intentId },
+    IntentBook__IntentNotFound { intentId },
+    IntentBook__CannotSpendIntentThatIsNotOpen { intentId },
+    IntentBook__SpendingPartiallyFillableIntentMustMakeProgress {},
+    IntentBook__IntentVersionsCannotChangeValidBeforeWhenSpent {},
+    IntentBook__IntentVersionsCannotChangeValidAfterWhenSpent {},
+    IntentBook__FillGraphCannotBeEmpty {},
+    IntentBook__IntentPredecessorRootDoesNotMatch {},
+    IntentBook__InvalidTimestamp {},
+    IntentBook__IntentIdAuthorMismatch {},
+    IntentBook__PercentageCannotBeZero {},
+    UnauthorizedCaller {},
+    InsufficientAllowance {},
+    InsufficientIntentBalance { intentBalance, amount },
+    UnsupportedMToken {},
+    MTokenPaused {},
+    MTokenDestroyed {},
+    CallerNotIntentBook {},
+    InvalidSignature {},
+    AuthorMismatch {},
+    IntentIDMismatch {},
+    InsufficientMTokens {},
+    MissmatchedMToken {},
+    InvalidNonce {},
+    ReceiptNotFound {},
+    UnauthorizedRedemption {},
+    UnauthorizedReceiptIssuance {},
+    ReceiptAlreadyLocked {},
+    ReceiptNotLocked {},
+    UnauthorizedLockOperation {},
+    SolutionLib__EmptyIntentsAndReceipts {},
+    SolutionLib__InputOutputMismatch { sumInput, sumIntentOutput, sumReceiptOutput },
+    SolutionLib__SolutionMintsTokens {},
+    SolutionLib__InputOutputTokenTypeMismatch { inputMToken, outputMToken },
+    SolutionLib__IntentAmountMismatch {},
+    SolutionLib__ReceiptAmountMismatch {},
+    SolutionLib__UnsupportedOutcomeStructure {},
+    SolutionLib__UnsupportedFillStructure {},
+    SolutionLib__InvalidFillGraphForEASIntent {},
+    SolutionLib__ExactAnySingleMustBeFulfilledWithReceipts { fillRec },
+    SolutionLib__MismatchBetweenInputAndOutputOwners {},
+    SolutionLib__InsufficientReceiptsToFillPASIntent { expectedReceiptTotal, actualReceiptTotal },
+    SolutionLib__MinimumFillAmountNotSatisfied { minimumReceiptAmount, actualReceiptAmount },
+    SolutionLib__AnySingleExactlyIntentMustHaveExactlyOneOutcomeToken {},
+    SolutionLib__AnySingleExactlyIntentMustHaveExactlyOneOutcomeTokenAmount {},
+    Teller__InvalidInitializationParameters {},
+    Teller__Paused {},
+    Teller__InvalidMedusaAddress {},
+    Teller__AssetNotSupported {},
+    Teller__ZeroAmount {},
+    Teller__CannotWithdrawAmount {},
+    Teller__MinimumDepositShareAmountNotMet {},
+    Teller__InsufficientSharesForWithdrawalFee {},
+    Teller__CannotRemoveSupportedAssetWithNonZeroBalance {},
+    Teller__DepositorDoesNotHaveEnoughShares { shares },
+    Teller__InvalidFeePercentage { feePercentage },
+    Teller__CannotWithdrawZeroShares {},
+    Teller__InvalidRate {},
+    MTokenVault__InsufficientBalance { asset, balance },
+    MTokenVault__CannotEnterZeroAmount {},
+    MTokenVault__CannotEnterWithoutMintingShares {},
+    MTokenVault__CannotExitZeroAmount {},
+    MTokenVault__CannotExitWithoutBurningShares {},
+}
+
+impl std::error::Error for AllErrors {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, U256};
+    use alloy::sol_types::SolError;
+
+    use crate::types::sol_types::All;
+
+    #[test]
+    fn test_decode_and_render_revert() {
+        let intent_id = B256::repeat_byte(0x11);
+        let data: Bytes = All::SolutionValidator__PercentageIntentNotSatisfied {
+            intentIdx: U256::from(2),
+            amtSpent: U256::from(10),
+            expectedSpentAmt: U256::from(20),
+        }
+        .abi_encode()
+        .into();
+        let decoded = decode_revert(&data).unwrap();
+        assert_eq!(
+            decoded.to_string(),
+            "SolutionValidator__PercentageIntentNotSatisfied { intentIdx: 2, amtSpent: 10, expectedSpentAmt: 20 }"
+        );
+
+        let data: Bytes = All::IntentBook__IntentNotFound {
+            intentId: intent_id,
+        }
+        .abi_encode()
+        .into();
+        assert!(matches!(
+            decode_revert(&data),
+            Some(AllErrors::IntentBook__IntentNotFound(e)) if e.intentId == intent_id
+        ));
+        assert!(decode_revert(&Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef])).is_none());
+
+        let data: Bytes = All::IntentBook__UnauthorizedCancellationAttempt {}
+            .abi_encode()
+            .into();
+        assert_eq!(
+            decode_revert(&data).unwrap().to_string(),
+            "IntentBook__UnauthorizedCancellationAttempt"
+        );
+    }
+
+    #[test]
+    fn test_decode_rpc_revert() {
+        let intent_id = B256::repeat_byte(0x22);
+        let data: Bytes = All::IntentValidator__CancelError__IntentNotOpen {
+            intentId: intent_id,
+        }
+        .abi_encode()
+        .into();
+        let err = jsonrpsee::core::ClientError::Call(jsonrpsee::types::ErrorObject::owned(
+            3,
+            "execution reverted",
+            Some(data),
+        ));
+        assert!(matches!(
+            decode_rpc_revert(&err),
+            Some(AllErrors::IntentValidator__CancelError__IntentNotOpen(e)) if e.intentId == intent_id
+        ));
+    }
+}
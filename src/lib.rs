@@ -1,27 +1,41 @@
 pub mod client;
 pub mod error;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod solver;
+#[cfg(test)]
+mod test_support;
 pub mod types;
 
+use std::time::Duration;
+
 use alloy::primitives::{Address, B256, U256};
 
 use crate::{
-    client::{MedusaRpcClient, SpokeClient},
-    types::sol_types::FastWithdrawalPermit,
+    client::{
+        IntentTracker, MedusaRpcClient, SpokeClient,
+        intent_tracker::{TrackOutcome, TrackerConfig},
+    },
+    error::{CancelIntentError, Error},
+    types::{
+        deployment::ArcadiaDeployment,
+        intents::{IntentId, IntentPhase, IntentState},
+        rpc_payloads::CancelIntent,
+        sol_types::{CrossChainIntent, FastWithdrawalPermit},
+        token::Token,
+        token_registry::TokenRegistryError,
+    },
 };
 
 pub async fn fast_withdraw_mtoken(
     signer: &(impl alloy::signers::Signer + Send + Sync),
     medusa_client: &impl MedusaRpcClient,
     spoke_client: &SpokeClient,
-    arcadia_chain_id: u64,
-    mtoken_manager: Address,
+    deployment: &ArcadiaDeployment,
     permit: FastWithdrawalPermit,
     receiver: Address,
-) -> Result<(B256, B256), anyhow::Error> {
-    let user_signature = permit
-        .clone()
-        .sign(signer, arcadia_chain_id, mtoken_manager)
-        .await?;
+) -> Result<(B256, B256), Error> {
+    let user_signature = permit.clone().sign(signer, deployment).await?;
     let (arcadia_hash, operator_signature) = medusa_client
         .fast_withdraw_mtoken(permit.clone(), user_signature.clone())
         .await?;
@@ -47,3 +61,156 @@ pub fn build_fast_withdrawal_permit(
         caller,
     }
 }
+
+/// [`build_fast_withdrawal_permit`] for a registry token. `amount` is in 18 decimals.
+///
+/// Fails if the token's spoke chain id does not fit in a `u32`.
+pub fn build_fast_withdrawal_permit_for_token(
+    token: &Token,
+    amount: U256,
+    user: Address,
+    caller: Address,
+) -> Result<FastWithdrawalPermit, TokenRegistryError> {
+    Ok(build_fast_withdrawal_permit(
+        token.spoke_chain_id_u32()?,
+        token.spoke_address,
+        amount,
+        user,
+        caller,
+    ))
+}
+
+/// Signs and publishes a cross-chain intent, then tracks it with an [`IntentTracker`] polling
+/// every `poll_interval` until it is withdrawn to the destination chain or can no longer get
+/// there. A failed intent is returned too; check the phase of `history` in the outcome.
+///
+/// Fails with [`Error::Timeout`] if tracking has not ended after `timeout`.
+pub async fn publish_cross_chain_intent<C>(
+    signer: &(impl alloy::signers::Signer + Send + Sync),
+    medusa_client: &C,
+    deployment: &ArcadiaDeployment,
+    intent: CrossChainIntent,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<TrackOutcome, Error>
+where
+    C: MedusaRpcClient + Clone + Send + Sync + 'static,
+{
+    let signature = intent.sign(signer, deployment).await?;
+    let intent_id = medusa_client
+        .publish_cross_chain_intent(intent, signature)
+        .await?;
+    let config = TrackerConfig {
+        poll_interval,
+        until: IntentPhase::WithdrawnToSpoke,
+        timeout,
+    };
+    IntentTracker::new(medusa_client.clone(), intent_id, config)
+        .await_terminal()
+        .await
+}
+
+/// Cancels an open intent authored by `signer`, then polls its status every `poll_interval`
+/// until Medusa reports it cancelled. Returns the cancellation transaction hash.
+///
+/// Fails with [`CancelIntentError::Timeout`] if the intent is not cancelled after `timeout`.
+pub async fn cancel_intent(
+    signer: &(impl alloy::signers::Signer + Send + Sync),
+    medusa_client: &impl MedusaRpcClient,
+    deployment: &ArcadiaDeployment,
+    intent_id: IntentId,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<B256, CancelIntentError> {
+    let nonce = medusa_client
+        .get_nonce(signer.address())
+        .await?
+        .checked_add(U256::from(1))
+        .ok_or(CancelIntentError::NonceOverflow)?;
+    let payload = CancelIntent {
+        nonce,
+        intentId: intent_id,
+    }
+    .sign(signer, deployment)
+    .await?;
+    let tx_hash = medusa_client.cancel_intent(payload).await?;
+
+    let wait_cancelled = async {
+        loop {
+            match medusa_client.get_intent_status(intent_id).await? {
+                Some(IntentState::Cancelled) => return Ok(tx_hash),
+                Some(state @ (IntentState::Solved | IntentState::Expired | IntentState::Error)) => {
+                    return Err(CancelIntentError::NotCancelled(state));
+                }
+                _ => tokio::time::sleep(poll_interval).await,
+            }
+        }
+    };
+    tokio::time::timeout(timeout, wait_cancelled)
+        .await
+        .map_err(|_| CancelIntentError::Timeout(timeout))?
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use alloy::signers::local::PrivateKeySigner;
+
+    use super::*;
+    use crate::test_support::{start_mock_medusa, test_deployment};
+    use crate::types::intent_builder::CrossChainIntentBuilder;
+    use crate::types::intents::IntentEvent;
+
+    #[tokio::test]
+    async fn test_publish_cross_chain_intent_tracks_to_spoke() {
+        let deployment = test_deployment();
+        let (medusa, client, _handle) = start_mock_medusa().await;
+        let signer = PrivateKeySigner::random();
+        let intent = CrossChainIntentBuilder::new(signer.address())
+            .sell(Address::repeat_byte(0xaa), U256::from(100))
+            .destination_chain(42161)
+            .native_outcome(U256::from(1))
+            .valid_for(Duration::from_secs(600))
+            .fetch_nonce(&client)
+            .await
+            .unwrap()
+            .build()
+            .unwrap();
+        let intent_id = intent.intent_id();
+
+        let publish = tokio::spawn({
+            let client = client.clone();
+            async move {
+                publish_cross_chain_intent(
+                    &signer,
+                    &client,
+                    &deployment,
+                    intent,
+                    Duration::from_millis(10),
+                    Duration::from_secs(5),
+                )
+                .await
+            }
+        });
+        while client.get_intent_status(intent_id).await.unwrap() != Some(IntentState::Open) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // Being solved does not end tracking, the withdrawal to the destination chain does.
+        medusa
+            .record_event(intent_id, IntentEvent::Solve(B256::ZERO, None))
+            .unwrap();
+        medusa.set_intent_state(intent_id, IntentState::Solved);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!publish.is_finished());
+        for event in [
+            IntentEvent::Redeem(B256::ZERO),
+            IntentEvent::Withdraw(B256::ZERO),
+            IntentEvent::WithdrawReachSpoke(),
+        ] {
+            medusa.record_event(intent_id, event).unwrap();
+        }
+        let outcome = publish.await.unwrap().unwrap();
+        assert_eq!(outcome.lineage, vec![intent_id]);
+        assert_eq!(outcome.history.phase(), IntentPhase::WithdrawnToSpoke);
+    }
+}
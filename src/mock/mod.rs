@@ -0,0 +1,7 @@
+//! In-process stand-ins for Medusa, for testing solvers and wallets end to end.
+
+mod rpc;
+mod ws;
+
+pub use rpc::MockMedusa;
+pub use ws::MockMedusaWs;
@@ -0,0 +1,733 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use alloy::dyn_abi::TypedData;
+use alloy::primitives::{Address, B256, Bytes, Signature, U256};
+use alloy::signers::Signer;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::{SolInterface, SolStruct};
+use jsonrpsee::core::{RpcResult, async_trait};
+use jsonrpsee::server::{Server, ServerHandle};
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::types::error::{INVALID_PARAMS_CODE, METHOD_NOT_FOUND_CODE};
+
+use crate::client::MedusaRpcServer;
+use crate::types::deployment::ArcadiaDeployment;
+use crate::types::intents::{
+    FillStructure, Intent, IntentErrorType, IntentEvent, IntentHistory, IntentHistoryError,
+    IntentId, IntentState, Outcome, OutcomeAssetStructure, SignedIntent, current_timestamp_sec,
+};
+use crate::types::refinement::RefinementStatus;
+use crate::types::rpc_payloads::{
+    MaximumWithdrawPreview, SignablePayload, SignedAddSolver, SignedCancelIntent,
+    SignedVaultDeposit, SignedVaultWithdraw, SignedWithdraw,
+};
+use crate::types::sol_types::All::{self, AllErrors};
+use crate::types::sol_types::{CrossChainIntent, FastWithdrawalPermit};
+use crate::types::solution::{OutType, SignedSolution};
+
+/// The mock's plain-intent view of a cross-chain intent, for lookups returning an [`Intent`].
+fn cross_chain_as_intent(intent: &CrossChainIntent) -> Intent {
+    Intent {
+        author: intent.author,
+        valid_before: intent.validBefore,
+        valid_after: U256::ZERO,
+        nonce: intent.nonce,
+        src_m_token: intent.srcMToken,
+        src_amount: intent.srcAmount,
+        outcome: Outcome {
+            m_tokens: vec![intent.outcomeToken],
+            m_amounts: vec![intent.outcomeAmount],
+            outcome_asset_structure: OutcomeAssetStructure::AnySingle,
+            fill_structure: FillStructure::Exact,
+        },
+    }
+}
+
+/// JSON-RPC error code under which reverts are reported, with the revert data in `data`.
+const REVERT_CODE: i32 = 3;
+
+macro_rules! reverted {
+    ($name:ident { $($body:tt)* }) => {
+        revert(AllErrors::$name(All::$name { $($body)* }))
+    };
+}
+
+#[derive(Default)]
+struct State {
+    intents: HashMap<IntentId, (Intent, IntentState, IntentHistory)>,
+    cross_chain_intents: HashMap<IntentId, (CrossChainIntent, IntentState, IntentHistory)>,
+    nonces: HashMap<Address, U256>,
+    solutions: HashMap<IntentId, SignedSolution>,
+    solutions_by_solver: HashMap<Address, Vec<SignedSolution>>,
+    solvers: Vec<Address>,
+    refinements: HashMap<IntentId, RefinementStatus>,
+    balances: HashMap<(Address, Address), U256>,
+}
+
+/// An in-memory Medusa implementing [`MedusaRpcServer`].
+///
+/// It verifies signatures and nonces, validates intents with [`Intent::validate`] and keeps
+/// intent statuses and histories. Solving is driven by the test through [`MockMedusa::settle`].
+/// Vault methods are not supported.
+#[derive(Clone)]
+pub struct MockMedusa {
+    deployment: ArcadiaDeployment,
+    operator: PrivateKeySigner,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockMedusa {
+    pub fn new(deployment: ArcadiaDeployment) -> Self {
+        Self {
+            deployment,
+            operator: PrivateKeySigner::random(),
+            state: Default::default(),
+        }
+    }
+
+    /// Serves the mock over HTTP and WS on `addr`, e.g. `127.0.0.1:0`.
+    pub async fn start(self, addr: SocketAddr) -> anyhow::Result<(SocketAddr, ServerHandle)> {
+        let server = Server::builder().build(addr).await?;
+        let addr = server.local_addr()?;
+        Ok((addr, server.start(self.into_rpc())))
+    }
+
+    pub fn deployment(&self) -> &ArcadiaDeployment {
+        &self.deployment
+    }
+
+    /// The operator co-signing fast withdrawals.
+    pub fn operator_address(&self) -> Address {
+        self.operator.address()
+    }
+
+    pub fn set_mtoken_balance(&self, user: Address, mtoken: Address, amount: U256) {
+        self.state().balances.insert((user, mtoken), amount);
+    }
+
+    pub fn set_refinement(&self, intent_id: IntentId, refinement: RefinementStatus) {
+        self.state().refinements.insert(intent_id, refinement);
+    }
+
+    /// Forces the status of an intent or cross-chain intent, e.g. to `Expired`.
+    pub fn set_intent_state(&self, intent_id: IntentId, state: IntentState) {
+        let mut guard = self.state();
+        let current = match guard.intents.get_mut(&intent_id) {
+            Some((_, current, _)) => Some(current),
+            None => guard
+                .cross_chain_intents
+                .get_mut(&intent_id)
+                .map(|(_, current, _)| current),
+        };
+        if let Some(current) = current {
+            *current = state;
+        }
+    }
+
+    /// Records `event` in the history of an intent or cross-chain intent without changing its
+    /// status, e.g. to simulate withdrawals.
+    pub fn record_event(
+        &self,
+        intent_id: IntentId,
+        event: IntentEvent,
+    ) -> Result<(), IntentHistoryError> {
+        let mut guard = self.state();
+        let history = match guard.intents.get_mut(&intent_id) {
+            Some((_, _, history)) => Some(history),
+            None => guard
+                .cross_chain_intents
+                .get_mut(&intent_id)
+                .map(|(_, _, history)| history),
+        };
+        match history {
+            Some(history) => history.apply_event_at(event, current_timestamp_sec()),
+            None => Ok(()),
+        }
+    }
+
+    /// Records a failure of `intent_id`. Only failures that end the intent's lifecycle (see
+    /// [`IntentHistory::phase`]) change its status to `Error`.
+    pub fn fail_intent(
+        &self,
+        intent_id: IntentId,
+        error_type: IntentErrorType,
+    ) -> Result<(), IntentHistoryError> {
+        if let Some((_, state, history)) = self.state().intents.get_mut(&intent_id) {
+            history.apply_event_at(
+                IntentEvent::Error(error_type, B256::random()),
+                current_timestamp_sec(),
+            )?;
+            if history.phase().is_terminal() {
+                *state = IntentState::Error;
+            }
+        }
+        Ok(())
+    }
+
+    /// Settles a solution: its input intents become solved, and intent outputs filling them
+    /// become open as their remainders. Returns the settlement transaction hash.
+    pub fn settle(&self, solution: SignedSolution) -> RpcResult<B256> {
+        let tx_hash = B256::random();
+        let now = current_timestamp_sec();
+        let mut state = self.state();
+        for (idx, intent_id) in solution.solution.intent_ids.iter().enumerate() {
+            match state.intents.get(intent_id) {
+                Some((_, IntentState::Open, _)) => {}
+                Some(_) => {
+                    return Err(reverted!(IntentBook__CannotSpendIntentThatIsNotOpen {
+                        intentId: *intent_id,
+                    }));
+                }
+                None => {
+                    return Err(reverted!(IntentBook__IntentNotFound {
+                        intentId: *intent_id,
+                    }));
+                }
+            }
+            let remainder = solution
+                .solution
+                .fill_graph
+                .iter()
+                .find(|fill| fill.in_idx as usize == idx && fill.out_type == OutType::Intent)
+                .and_then(|fill| solution.solution.intent_outputs.get(fill.out_idx as usize))
+                .cloned();
+            if let Some(remainder) = &remainder {
+                let mut history = IntentHistory::default();
+                history
+                    .apply_event_at(IntentEvent::Publish(tx_hash), now)
+                    .map_err(internal)?;
+                state.intents.insert(
+                    remainder.intent_id(),
+                    (remainder.clone(), IntentState::Open, history),
+                );
+            }
+            let (_, intent_state, history) = state.intents.get_mut(intent_id).unwrap();
+            *intent_state = IntentState::Solved;
+            history
+                .apply_event_at(
+                    IntentEvent::Solve(tx_hash, remainder.map(|r| r.intent_id())),
+                    now,
+                )
+                .map_err(internal)?;
+            state.solutions.insert(*intent_id, solution.clone());
+        }
+        if let Some(solver) = solution.try_recover_address() {
+            state
+                .solutions_by_solver
+                .entry(solver)
+                .or_default()
+                .push(solution);
+        }
+        Ok(tx_hash)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn recover<T: SignablePayload>(&self, payload: &T, signature: &Bytes) -> RpcResult<Address> {
+        recover(
+            payload.eip712_signing_hash(&T::domain(&self.deployment)),
+            signature,
+        )
+    }
+
+    /// Checks `nonce` is above `user`'s current nonce and makes it the current one.
+    fn use_nonce(state: &mut State, user: Address, nonce: U256) -> RpcResult<()> {
+        let current = state.nonces.entry(user).or_default();
+        if nonce <= *current {
+            return Err(reverted!(IntentBook__InvalidIntentNonce {}));
+        }
+        *current = nonce;
+        Ok(())
+    }
+}
+
+fn recover(hash: B256, signature: &Bytes) -> RpcResult<Address> {
+    Signature::from_raw(signature)
+        .and_then(|signature| signature.recover_address_from_prehash(&hash))
+        .map_err(|e| invalid_params(format!("invalid signature: {e}")))
+}
+
+fn revert(err: impl Into<AllErrors>) -> ErrorObjectOwned {
+    let err: AllErrors = err.into();
+    ErrorObjectOwned::owned(
+        REVERT_CODE,
+        format!("execution reverted: {err}"),
+        Some(Bytes::from(err.abi_encode())),
+    )
+}
+
+fn invalid_params(message: impl Into<String>) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(INVALID_PARAMS_CODE, message, None::<()>)
+}
+
+fn internal(err: impl std::fmt::Display) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(-32000, err.to_string(), None::<()>)
+}
+
+fn unsupported<T>() -> RpcResult<T> {
+    Err(ErrorObjectOwned::owned(
+        METHOD_NOT_FOUND_CODE,
+        "not supported by MockMedusa",
+        None::<()>,
+    ))
+}
+
+#[async_trait]
+impl MedusaRpcServer for MockMedusa {
+    async fn get_vault_share_price_data(
+        &self,
+        _teller_address: Address,
+    ) -> RpcResult<(U256, U256)> {
+        unsupported()
+    }
+
+    async fn get_mtoken_balance_in_vault(
+        &self,
+        _teller_address: Address,
+        _mtoken_address: Address,
+    ) -> RpcResult<U256> {
+        unsupported()
+    }
+
+    async fn get_depositor_vault_shares(
+        &self,
+        _teller_address: Address,
+        _depositor_address: Address,
+    ) -> RpcResult<U256> {
+        unsupported()
+    }
+
+    async fn get_vault_total_asset_value(&self, _teller_address: Address) -> RpcResult<U256> {
+        unsupported()
+    }
+
+    async fn get_vault_total_shares(&self, _teller_address: Address) -> RpcResult<U256> {
+        unsupported()
+    }
+
+    async fn preview_deposit_to_vault(
+        &self,
+        _teller_address: Address,
+        _asset: Address,
+        _amount: U256,
+    ) -> RpcResult<U256> {
+        unsupported()
+    }
+
+    async fn deposit_to_vault(&self, _payload: SignedVaultDeposit) -> RpcResult<B256> {
+        unsupported()
+    }
+
+    async fn preview_maximum_withdraw_from_vault(
+        &self,
+        _teller_address: Address,
+        _asset: Address,
+        _shares: U256,
+        _fee_percentage: u16,
+    ) -> RpcResult<MaximumWithdrawPreview> {
+        unsupported()
+    }
+
+    async fn withdraw_from_vault(&self, _payload: SignedVaultWithdraw) -> RpcResult<B256> {
+        unsupported()
+    }
+
+    async fn get_mtoken_balance_by_author(
+        &self,
+        user: Address,
+        mtoken_address: Address,
+    ) -> RpcResult<U256> {
+        Ok(self
+            .state()
+            .balances
+            .get(&(user, mtoken_address))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn get_solution_for_intent(&self, intent_id: B256) -> RpcResult<Option<SignedSolution>> {
+        Ok(self.state().solutions.get(&intent_id).cloned())
+    }
+
+    async fn get_connected_solvers(&self) -> RpcResult<Vec<Address>> {
+        Ok(self.state().solvers.clone())
+    }
+
+    async fn get_intent_ids_by_author(&self, author: Address) -> RpcResult<Vec<IntentId>> {
+        Ok(self
+            .state()
+            .intents
+            .iter()
+            .filter(|(_, (intent, ..))| intent.author == author)
+            .map(|(id, _)| *id)
+            .collect())
+    }
+
+    async fn get_active_intents_by_author(&self, author: Address) -> RpcResult<Vec<Intent>> {
+        Ok(self
+            .state()
+            .intents
+            .values()
+            .filter(|(intent, state, _)| intent.author == author && *state == IntentState::Open)
+            .map(|(intent, ..)| intent.clone())
+            .collect())
+    }
+
+    async fn get_liquidity_intents_by_author(&self, _author: Address) -> RpcResult<Vec<Intent>> {
+        unsupported()
+    }
+
+    async fn get_bridge_intents_by_author(&self, _author: Address) -> RpcResult<Vec<Intent>> {
+        unsupported()
+    }
+
+    async fn get_latest_liquidity(&self, intent_id: IntentId) -> RpcResult<IntentId> {
+        let state = self.state();
+        let mut latest = intent_id;
+        while let Some((.., history)) = state.intents.get(&latest) {
+            match history.remaining_intent_id {
+                Some(next) if next != latest => latest = next,
+                _ => break,
+            }
+        }
+        Ok(latest)
+    }
+
+    async fn get_solutions_for_solver(
+        &self,
+        solver_address: Address,
+    ) -> RpcResult<Vec<SignedSolution>> {
+        Ok(self
+            .state()
+            .solutions_by_solver
+            .get(&solver_address)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_intent(&self, intent_id: B256) -> RpcResult<Option<Intent>> {
+        Ok(self
+            .state()
+            .intents
+            .get(&intent_id)
+            .map(|(intent, ..)| intent.clone()))
+    }
+
+    async fn get_intent_status(&self, intent_id: B256) -> RpcResult<Option<IntentState>> {
+        let state = self.state();
+        Ok(state
+            .intents
+            .get(&intent_id)
+            .map(|(_, intent_state, _)| intent_state.clone())
+            .or_else(|| {
+                state
+                    .cross_chain_intents
+                    .get(&intent_id)
+                    .map(|(_, intent_state, _)| intent_state.clone())
+            }))
+    }
+
+    async fn propose_intent(&self, intent: SignedIntent) -> RpcResult<(B256, IntentId)> {
+        let SignedIntent { intent, signature } = intent;
+        let signer = recover(intent.signing_hash(&self.deployment), &signature)?;
+        if signer != intent.author {
+            return Err(reverted!(IntentBook__InvalidSignature {}));
+        }
+        let intent_id = intent.intent_id();
+        let mut state = self.state();
+        if state.intents.contains_key(&intent_id) {
+            return Err(reverted!(
+                IntentValidator__PublishError__IntentAlreadyExists {
+                    intentId: intent_id,
+                }
+            ));
+        }
+        let nonce = state
+            .nonces
+            .get(&intent.author)
+            .copied()
+            .unwrap_or_default();
+        let now = current_timestamp_sec();
+        intent.validate(now, nonce).map_err(revert)?;
+        state.nonces.insert(intent.author, intent.nonce);
+
+        let tx_hash = B256::random();
+        let mut history = IntentHistory::default();
+        history
+            .apply_event_at(IntentEvent::Publish(tx_hash), now)
+            .map_err(internal)?;
+        state
+            .intents
+            .insert(intent_id, (intent, IntentState::Open, history));
+        Ok((tx_hash, intent_id))
+    }
+
+    async fn create_refinement(&self, intent: Intent) -> RpcResult<IntentId> {
+        let intent_id = intent.intent_id();
+        self.state()
+            .refinements
+            .entry(intent_id)
+            .or_insert(RefinementStatus::RefinementNotFound);
+        Ok(intent_id)
+    }
+
+    async fn query_refinement(&self, intent_id: IntentId) -> RpcResult<Option<RefinementStatus>> {
+        Ok(self.state().refinements.get(&intent_id).cloned())
+    }
+
+    async fn cancel_intent(&self, payload: SignedCancelIntent) -> RpcResult<B256> {
+        let signer = self.recover(&payload.payload, &payload.signature)?;
+        let intent_id = payload.payload.intentId;
+        let mut state = self.state();
+        let author = match state.intents.get(&intent_id) {
+            Some((intent, IntentState::Open, _)) => intent.author,
+            Some(_) => {
+                return Err(reverted!(IntentValidator__CancelError__IntentNotOpen {
+                    intentId: intent_id,
+                }));
+            }
+            None => {
+                return Err(reverted!(IntentBook__IntentNotFound {
+                    intentId: intent_id,
+                }));
+            }
+        };
+        if signer != author {
+            return Err(reverted!(IntentBook__UnauthorizedCancellationAttempt {}));
+        }
+        Self::use_nonce(&mut state, signer, payload.payload.nonce)?;
+
+        let tx_hash = B256::random();
+        let (_, intent_state, history) = state.intents.get_mut(&intent_id).unwrap();
+        *intent_state = IntentState::Cancelled;
+        history
+            .apply_event_at(IntentEvent::Cancel(tx_hash), current_timestamp_sec())
+            .map_err(internal)?;
+        Ok(tx_hash)
+    }
+
+    async fn get_history_for_intent(&self, intent_id: B256) -> RpcResult<(IntentHistory, Intent)> {
+        let state = self.state();
+        state
+            .intents
+            .get(&intent_id)
+            .map(|(intent, _, history)| (history.clone(), intent.clone()))
+            .or_else(|| {
+                state
+                    .cross_chain_intents
+                    .get(&intent_id)
+                    .map(|(intent, _, history)| (history.clone(), cross_chain_as_intent(intent)))
+            })
+            .ok_or_else(|| invalid_params(format!("intent {intent_id} not found")))
+    }
+
+    async fn withdraw_mtokens(&self, payload: SignedWithdraw) -> RpcResult<B256> {
+        let signer = self.recover(&payload.payload, &payload.signature)?;
+        let withdraw = &payload.payload;
+        if signer != withdraw.address {
+            return Err(invalid_params("signer is not the withdrawing address"));
+        }
+        let mut state = self.state();
+        Self::use_nonce(&mut state, signer, withdraw.nonce)?;
+        let balance = state
+            .balances
+            .entry((withdraw.address, withdraw.mtoken))
+            .or_default();
+        *balance = balance
+            .checked_sub(withdraw.amount)
+            .ok_or_else(|| invalid_params("insufficient mToken balance"))?;
+        Ok(B256::random())
+    }
+
+    async fn fast_withdraw_mtokens_with_witness(
+        &self,
+        _permit_and_witness: TypedData,
+        _user_signature: Bytes,
+    ) -> RpcResult<(B256, Bytes)> {
+        unsupported()
+    }
+
+    async fn fast_withdraw_mtoken(
+        &self,
+        permit: FastWithdrawalPermit,
+        user_signature: Bytes,
+    ) -> RpcResult<(B256, Bytes)> {
+        let hash = permit.eip712_signing_hash(&self.deployment.fast_withdrawal_domain());
+        if recover(hash, &user_signature)? != permit.user {
+            return Err(invalid_params("permit is not signed by its user"));
+        }
+        let operator_signature = self.operator.sign_hash(&hash).await.map_err(internal)?;
+        Ok((
+            B256::random(),
+            operator_signature.as_bytes().to_vec().into(),
+        ))
+    }
+
+    async fn get_failed_intents_since_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> RpcResult<Vec<(IntentHistory, Intent)>> {
+        Ok(self
+            .state()
+            .intents
+            .values()
+            .filter(|(.., history)| history.error_timestamp.is_some_and(|t| t >= timestamp))
+            .map(|(intent, _, history)| (history.clone(), intent.clone()))
+            .collect())
+    }
+
+    async fn get_nonce(&self, user: Address) -> RpcResult<U256> {
+        Ok(self.state().nonces.get(&user).copied().unwrap_or_default())
+    }
+
+    async fn request_add_solver(&self, payload: SignedAddSolver) -> RpcResult<()> {
+        let signer = self.recover(&payload.payload, &payload.signature)?;
+        if signer != payload.payload.address {
+            return Err(invalid_params("signer is not the solver address"));
+        }
+        let mut state = self.state();
+        if !state.solvers.contains(&signer) {
+            state.solvers.push(signer);
+        }
+        Ok(())
+    }
+
+    async fn publish_cross_chain_intent(
+        &self,
+        intent: CrossChainIntent,
+        signature: Bytes,
+    ) -> RpcResult<B256> {
+        let hash = intent.eip712_signing_hash(&self.deployment.intent_domain());
+        if recover(hash, &signature)? != intent.author {
+            return Err(reverted!(IntentBook__InvalidSignature {}));
+        }
+        let intent_id = intent.intent_id();
+        let mut state = self.state();
+        let nonce = state
+            .nonces
+            .get(&intent.author)
+            .copied()
+            .unwrap_or_default();
+        let now = current_timestamp_sec();
+        intent.validate(now, nonce).map_err(revert)?;
+        state.nonces.insert(intent.author, intent.nonce);
+
+        let tx_hash = B256::random();
+        let mut history = IntentHistory::default();
+        history
+            .apply_event_at(IntentEvent::Publish(tx_hash), now)
+            .map_err(internal)?;
+        state
+            .cross_chain_intents
+            .insert(intent_id, (intent, IntentState::Open, history));
+        Ok(intent_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::client::MedusaRpcClient;
+    use crate::error::CancelIntentError;
+    use crate::test_support::{propose_intent, start_mock_medusa, test_deployment};
+    use crate::types::intent_builder::IntentBuilder;
+    use crate::types::solution::Solution;
+
+    #[tokio::test]
+    async fn test_publish_and_cancel_intent() {
+        let deployment = test_deployment();
+        let (medusa, client, _handle) = start_mock_medusa().await;
+        let signer = PrivateKeySigner::random();
+
+        let (signed, intent_id) = propose_intent(
+            &client,
+            &signer,
+            IntentBuilder::new(signer.address())
+                .sell(Address::repeat_byte(0xaa), U256::from(100))
+                .receive(Address::repeat_byte(0xbb), U256::from(1))
+                .valid_for(Duration::from_secs(600)),
+        )
+        .await;
+        assert_eq!(
+            client.get_intent_status(intent_id).await.unwrap(),
+            Some(IntentState::Open)
+        );
+        assert_eq!(
+            client.get_nonce(signer.address()).await.unwrap(),
+            U256::from(1)
+        );
+        assert!(client.propose_intent(signed.clone()).await.is_err());
+
+        // A failed solve leaves the intent open, and it can still be solved afterwards.
+        medusa
+            .fail_intent(intent_id, IntentErrorType::Solve)
+            .unwrap();
+        assert_eq!(
+            client.get_intent_status(intent_id).await.unwrap(),
+            Some(IntentState::Open)
+        );
+        assert!(
+            medusa
+                .fail_intent(intent_id, IntentErrorType::Withdraw)
+                .is_err()
+        );
+        let mut builder = Solution::builder();
+        let input = builder.add_input(signed.intent.clone());
+        builder.deliver(input, input, U256::from(100)).unwrap();
+        let solution = builder.build_unchecked().sign(&signer).await;
+        medusa.settle(solution).unwrap();
+        let (history, _) = client.get_history_for_intent(intent_id).await.unwrap();
+        assert!(history.solve_timestamp.is_some());
+        assert_eq!(history.error_type, Some(IntentErrorType::Solve));
+
+        let signed = IntentBuilder::new(signer.address())
+            .sell(Address::repeat_byte(0xaa), U256::from(100))
+            .receive(Address::repeat_byte(0xbb), U256::from(1))
+            .valid_for(Duration::from_secs(600))
+            .fetch_nonce(&client)
+            .await
+            .unwrap()
+            .sign(&signer, &deployment)
+            .await
+            .unwrap();
+        let (_, intent_id) = client.propose_intent(signed).await.unwrap();
+
+        let cancel = |intent_id| {
+            crate::cancel_intent(
+                &signer,
+                &client,
+                medusa.deployment(),
+                intent_id,
+                Duration::from_millis(10),
+                Duration::from_secs(5),
+            )
+        };
+        cancel(intent_id).await.unwrap();
+        let (history, _) = client.get_history_for_intent(intent_id).await.unwrap();
+        assert!(history.cancel_timestamp.is_some());
+        assert!(matches!(
+            cancel(intent_id).await,
+            Err(CancelIntentError::IntentNotOpen(id)) if id == intent_id
+        ));
+
+        let last = IntentBuilder::new(signer.address())
+            .sell(Address::repeat_byte(0xaa), U256::from(100))
+            .receive(Address::repeat_byte(0xbb), U256::from(1))
+            .valid_for(Duration::from_secs(600))
+            .nonce(U256::MAX)
+            .sign(&signer, &deployment)
+            .await
+            .unwrap();
+        let (_, last_id) = client.propose_intent(last).await.unwrap();
+        assert!(matches!(
+            cancel(last_id).await,
+            Err(CancelIntentError::NonceOverflow)
+        ));
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+
+use crate::client::medusa_ws::broadcast_stream;
+use crate::types::deployment::ArcadiaDeployment;
+use crate::types::intents::{Intent, IntentId, SignedIntent};
+use crate::types::solution::{SignedSolution, Solution};
+use crate::types::ws::{ComplementaryWithdrawal, WsBroadcastMessage, WsPayload};
+
+/// Instructions from the test to every connected session.
+#[derive(Debug, Clone)]
+enum Command {
+    Broadcast(WsBroadcastMessage),
+    Ping(Vec<u8>),
+    Drop,
+    Close(Option<CloseFrame>),
+}
+
+#[derive(Default)]
+struct Shared {
+    payloads: Mutex<Vec<WsPayload>>,
+    solvers: Mutex<Vec<Address>>,
+    open_intents: Mutex<Vec<Intent>>,
+    solutions_for_intent: Mutex<HashMap<IntentId, Vec<Solution>>>,
+    solutions_for_solver: Mutex<HashMap<Address, Vec<Solution>>>,
+    pongs: AtomicUsize,
+    reply_delay: Mutex<Duration>,
+}
+
+/// A local Medusa WebSocket server for testing solvers.
+///
+/// Sessions must start with a validly signed `AddSolver`, otherwise they are closed with a
+/// policy violation. Afterwards every payload is recorded, `RequestOpenIntents` is answered
+/// with [`MockMedusaWs::set_open_intents`] and solution queries with the solutions set for
+/// them. Everything else the server sends is driven by the test.
+pub struct MockMedusaWs {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    commands: broadcast::Sender<Command>,
+    payload_send: broadcast::Sender<WsPayload>,
+    authenticated: watch::Receiver<usize>,
+    accept_handle: JoinHandle<()>,
+}
+
+impl MockMedusaWs {
+    /// Starts listening on a free localhost port. Sessions must sign their `AddSolver` for
+    /// `deployment`.
+    pub async fn start(deployment: ArcadiaDeployment) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let (commands, _) = broadcast::channel(256);
+        let (payload_send, _) = broadcast::channel(256);
+        let (authenticated_send, authenticated) = watch::channel(0);
+
+        let session = Session {
+            deployment,
+            shared: shared.clone(),
+            commands: commands.clone(),
+            payload_send: payload_send.clone(),
+            authenticated: Arc::new(authenticated_send),
+        };
+        let accept_handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(session.clone().run(stream));
+            }
+        });
+        Ok(Self {
+            addr,
+            shared,
+            commands,
+            payload_send,
+            authenticated,
+            accept_handle,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Sends `message` to every authenticated session.
+    pub fn broadcast(&self, message: WsBroadcastMessage) {
+        let _ = self.commands.send(Command::Broadcast(message));
+    }
+
+    pub fn ping(&self) {
+        let _ = self.commands.send(Command::Ping(b"mock".to_vec()));
+    }
+
+    /// Drops every connection without a close frame.
+    pub fn drop_connections(&self) {
+        let _ = self.commands.send(Command::Drop);
+    }
+
+    /// Closes every connection with a close frame.
+    pub fn close_connections(&self, code: CloseCode, reason: &str) {
+        let _ = self.commands.send(Command::Close(Some(CloseFrame {
+            code,
+            reason: reason.to_string().into(),
+        })));
+    }
+
+    pub fn set_open_intents(&self, intents: Vec<Intent>) {
+        *self.shared.open_intents.lock().unwrap() = intents;
+    }
+
+    pub fn set_solutions_for_intent(&self, intent_id: IntentId, solutions: Vec<Solution>) {
+        self.shared
+            .solutions_for_intent
+            .lock()
+            .unwrap()
+            .insert(intent_id, solutions);
+    }
+
+    pub fn set_solutions_for_solver(&self, solver: Address, solutions: Vec<Solution>) {
+        self.shared
+            .solutions_for_solver
+            .lock()
+            .unwrap()
+            .insert(solver, solutions);
+    }
+
+    /// Delays every reply by `delay`, e.g. to let queries time out before their answer arrives.
+    pub fn set_reply_delay(&self, delay: Duration) {
+        *self.shared.reply_delay.lock().unwrap() = delay;
+    }
+
+    /// Every payload received from authenticated sessions, in order.
+    pub fn received(&self) -> Vec<WsPayload> {
+        self.shared.payloads.lock().unwrap().clone()
+    }
+
+    /// Payloads received from now on.
+    pub fn payloads(&self) -> impl Stream<Item = WsPayload> + Send + 'static {
+        broadcast_stream(self.payload_send.subscribe())
+    }
+
+    /// Every `ProposeSolution` received, in order.
+    pub fn proposals(&self) -> Vec<(SignedSolution, Vec<SignedIntent>, bool)> {
+        self.received()
+            .into_iter()
+            .filter_map(|payload| match payload {
+                WsPayload::ProposeSolution(solution, intents, ComplementaryWithdrawal(c)) => {
+                    Some((solution, intents, c))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Solvers that authenticated, once per session.
+    pub fn solvers(&self) -> Vec<Address> {
+        self.shared.solvers.lock().unwrap().clone()
+    }
+
+    /// Number of pongs received in answer to [`MockMedusaWs::ping`].
+    pub fn pongs(&self) -> usize {
+        self.shared.pongs.load(Ordering::SeqCst)
+    }
+
+    /// Waits until `count` sessions have authenticated since the server started.
+    pub async fn wait_for_sessions(&self, count: usize) {
+        let mut authenticated = self.authenticated.clone();
+        let _ = authenticated.wait_for(|n| *n >= count).await;
+    }
+}
+
+impl Drop for MockMedusaWs {
+    fn drop(&mut self) {
+        self.accept_handle.abort();
+        let _ = self.commands.send(Command::Drop);
+    }
+}
+
+#[derive(Clone)]
+struct Session {
+    deployment: ArcadiaDeployment,
+    shared: Arc<Shared>,
+    commands: broadcast::Sender<Command>,
+    payload_send: broadcast::Sender<WsPayload>,
+    authenticated: Arc<watch::Sender<usize>>,
+}
+
+impl Session {
+    async fn run(self, stream: TcpStream) {
+        let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+            return;
+        };
+        if !self.authenticate(&mut ws).await {
+            let _ = ws
+                .close(Some(CloseFrame {
+                    code: CloseCode::Policy,
+                    reason: "expected a valid AddSolver".into(),
+                }))
+                .await;
+            return;
+        }
+        let mut commands = self.commands.subscribe();
+        self.authenticated.send_modify(|n| *n += 1);
+
+        let mut answered = 0u128;
+        loop {
+            tokio::select! {
+                message = ws.next() => match message {
+                    Some(Ok(Message::Text(raw))) => {
+                        let Ok(payload) = serde_json::from_str::<WsPayload>(&raw) else {
+                            tracing::warn!("Mock Medusa received an invalid payload: {}", raw);
+                            continue;
+                        };
+                        self.record(payload.clone());
+                        if let Some(reply) = self.reply(&payload, &mut answered) {
+                            let delay = *self.shared.reply_delay.lock().unwrap();
+                            tokio::time::sleep(delay).await;
+                            let reply = serde_json::to_string(&reply).unwrap();
+                            if ws.send(Message::Text(reply.into())).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Ping(ping))) => {
+                        let _ = ws.send(Message::Pong(ping)).await;
+                    }
+                    Some(Ok(Message::Pong(_))) => {
+                        self.shared.pongs.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => {}
+                },
+                command = commands.recv() => match command {
+                    Ok(Command::Broadcast(message)) => {
+                        let message = serde_json::to_string(&message).unwrap();
+                        if ws.send(Message::Text(message.into())).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(Command::Ping(data)) => {
+                        let _ = ws.send(Message::Ping(data.into())).await;
+                    }
+                    Ok(Command::Close(frame)) => {
+                        let _ = ws.close(frame).await;
+                        return;
+                    }
+                    Ok(Command::Drop) | Err(broadcast::error::RecvError::Closed) => return,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                },
+            }
+        }
+    }
+
+    async fn authenticate(&self, ws: &mut WebSocketStream<TcpStream>) -> bool {
+        let Some(Ok(Message::Text(raw))) = ws.next().await else {
+            return false;
+        };
+        let Ok(WsPayload::AddSolver(signed)) = serde_json::from_str::<WsPayload>(&raw) else {
+            return false;
+        };
+        match signed.recover_signer_address(&self.deployment) {
+            Ok(signer) if signer == signed.payload.address => {
+                self.shared.solvers.lock().unwrap().push(signer);
+                self.record(WsPayload::AddSolver(signed));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn record(&self, payload: WsPayload) {
+        self.shared.payloads.lock().unwrap().push(payload.clone());
+        let _ = self.payload_send.send(payload);
+    }
+
+    fn reply(&self, payload: &WsPayload, answered: &mut u128) -> Option<WsBroadcastMessage> {
+        let solutions = match payload {
+            WsPayload::RequestOpenIntents => {
+                let intents = self.shared.open_intents.lock().unwrap().clone();
+                return Some(WsBroadcastMessage::ExistingOpenIntents(intents));
+            }
+            WsPayload::GetSolutionsForIntent(intent_id) => self
+                .shared
+                .solutions_for_intent
+                .lock()
+                .unwrap()
+                .get(intent_id)
+                .cloned(),
+            WsPayload::GetSolutionsForSolver(solver) => self
+                .shared
+                .solutions_for_solver
+                .lock()
+                .unwrap()
+                .get(solver)
+                .cloned(),
+            _ => return None,
+        };
+        *answered += 1;
+        Some(WsBroadcastMessage::Solutions(
+            *answered,
+            solutions.unwrap_or_default(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::local::PrivateKeySigner;
+
+    use super::*;
+    use crate::client::medusa_ws::{
+        CloseReason, ReconnectConfig, SolverAuth, create_medusa_ws_client,
+        create_reconnecting_medusa_ws_client,
+    };
+    use crate::test_support::{add_solver, test_deployment};
+
+    #[tokio::test]
+    async fn test_solver_session() {
+        let server = MockMedusaWs::start(test_deployment()).await.unwrap();
+        let signer = PrivateKeySigner::random();
+        let add_solver = add_solver(&signer).await;
+
+        let mut forged = add_solver.clone();
+        forged.payload.address = Address::repeat_byte(0x44);
+        let rejected = create_medusa_ws_client(server.url(), forged).await.unwrap();
+        rejected.connection_events().for_each(|_| async {}).await;
+        assert!(matches!(
+            rejected.shutdown().await,
+            CloseReason::ClosedByServer(Some(frame)) if frame.code == CloseCode::Policy
+        ));
+
+        let config = ReconnectConfig {
+            initial_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let client = create_reconnecting_medusa_ws_client(
+            server.url(),
+            SolverAuth::Static(add_solver),
+            config,
+        )
+        .await
+        .unwrap();
+        server.wait_for_sessions(1).await;
+        assert_eq!(server.solvers(), vec![signer.address()]);
+
+        // The first subscriber also sees broadcasts received before it subscribed.
+        server.broadcast(WsBroadcastMessage::IntentsSolved(vec![], signer.address()));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut messages = Box::pin(client.subscribe());
+        assert!(matches!(
+            messages.next().await,
+            Some(WsBroadcastMessage::IntentsSolved(..))
+        ));
+        assert!(
+            client
+                .get_solutions_for_intent(IntentId::ZERO)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        // An answer that cannot belong to the query fails it instead of being mis-delivered.
+        let unrelated = Solution {
+            intent_ids: vec![IntentId::repeat_byte(0x55)],
+            intent_outputs: vec![],
+            receipt_outputs: vec![],
+            spend_graph: vec![],
+            fill_graph: vec![],
+        };
+        server.set_solutions_for_intent(IntentId::ZERO, vec![unrelated]);
+        assert!(
+            client
+                .get_solutions_for_intent(IntentId::ZERO)
+                .await
+                .is_err()
+        );
+
+        server.drop_connections();
+        server.wait_for_sessions(2).await;
+        // Query answers are broadcast too, so skip them until the resync arrives.
+        let resync = messages
+            .filter(|m| std::future::ready(matches!(m, WsBroadcastMessage::ExistingOpenIntents(_))))
+            .next()
+            .await;
+        assert!(resync.is_some());
+        assert_eq!(server.solvers().len(), 2);
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_late_answer_desyncs_session() {
+        let server = MockMedusaWs::start(test_deployment()).await.unwrap();
+        let signer = PrivateKeySigner::random();
+        let add_solver = add_solver(&signer).await;
+        let config = ReconnectConfig {
+            initial_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let client = create_reconnecting_medusa_ws_client(
+            server.url(),
+            SolverAuth::Static(add_solver),
+            config,
+        )
+        .await
+        .unwrap()
+        .with_request_timeout(Duration::from_millis(200));
+        server.wait_for_sessions(1).await;
+
+        let solution = Solution {
+            intent_ids: vec![IntentId::repeat_byte(0x55)],
+            intent_outputs: vec![],
+            receipt_outputs: vec![],
+            spend_graph: vec![],
+            fill_graph: vec![],
+        };
+        server.set_solutions_for_solver(signer.address(), vec![solution.clone()]);
+        server.set_reply_delay(Duration::from_millis(400));
+        assert!(
+            client
+                .get_solutions_for_intent(IntentId::ZERO)
+                .await
+                .is_err()
+        );
+
+        // Let the session notice the missed deadline. The late answer to the timed out query
+        // then arrives while the next one is pending and must not be taken as its answer.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.set_reply_delay(Duration::ZERO);
+        assert!(
+            client
+                .get_solutions_for_solver(signer.address())
+                .await
+                .is_err()
+        );
+
+        // A new connection starts in sync again.
+        server.drop_connections();
+        server.wait_for_sessions(2).await;
+        let solutions = client
+            .get_solutions_for_solver(signer.address())
+            .await
+            .unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].intent_ids, solution.intent_ids);
+        client.shutdown().await;
+    }
+}
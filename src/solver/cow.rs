@@ -0,0 +1,271 @@
+//! A reference coincidence-of-wants matcher.
+//!
+//! Two intents match when each sells what the other wants at compatible prices, e.g. A sells
+//! mUSDC for mETH and B sells mETH for mUSDC. The resulting solution is shaped as follows:
+//!
+//! - `intent_ids` lists the matched intents.
+//! - Each intent gets one receipt in the token it wants, owned by its author. The receipt is
+//!   funded by a move from the counterparty (`spend_graph`) and delivered by a fill record
+//!   (`fill_graph`). See [`SolutionBuilder::deliver`].
+//! - An intent that is only partially filled moves its unspent source amount into a child
+//!   intent with an incremented nonce and pro rata outcome amounts. The child is linked to its
+//!   parent by a fill record of type `Intent`. See [`SolutionBuilder::remainder`].
+
+use alloy::primitives::{Address, U256};
+
+use super::{Proposal, Strategy};
+use crate::client::OpenIntentBook;
+use crate::types::intents::{FillStructure, Intent, OutcomeAssetStructure};
+use crate::types::solution::{Solution, SolutionBuilder, SolutionBuilderError};
+
+/// What one side of a match receives and spends.
+struct Leg<'a> {
+    intent: &'a Intent,
+    /// Source amount moved to the counterparty.
+    spent: U256,
+    /// Outcome amount received from the counterparty.
+    received: U256,
+}
+
+/// The outcome amount `intent` asks for in `m_token`, if it accepts being filled with it alone.
+fn wanted_amount(intent: &Intent, m_token: Address) -> Option<U256> {
+    let outcome = &intent.outcome;
+    let idx = outcome.m_tokens.iter().position(|t| *t == m_token)?;
+    if outcome.outcome_asset_structure == OutcomeAssetStructure::All && outcome.m_tokens.len() > 1 {
+        return None;
+    }
+    outcome.m_amounts.get(idx).copied()
+}
+
+fn partially_fillable(intent: &Intent) -> bool {
+    matches!(
+        intent.outcome.fill_structure,
+        FillStructure::Minimum | FillStructure::PercentageFilled
+    )
+}
+
+fn mul_div_ceil(a: U256, b: U256, c: U256) -> Option<U256> {
+    Some(a.checked_mul(b)?.div_ceil(c))
+}
+
+fn legs<'a>(a: &'a Intent, b: &'a Intent) -> Option<(Leg<'a>, Leg<'a>)> {
+    if a.outcome.fill_structure == FillStructure::ConcreteRange
+        || b.outcome.fill_structure == FillStructure::ConcreteRange
+    {
+        return None;
+    }
+    let (sa, sb) = (a.src_amount, b.src_amount);
+    let ma = wanted_amount(a, b.src_m_token)?;
+    let mb = wanted_amount(b, a.src_m_token)?;
+    if sa.is_zero() || sb.is_zero() {
+        return None;
+    }
+
+    // Both fully filled.
+    if sb >= ma && sa >= mb {
+        return Some((
+            Leg {
+                intent: a,
+                spent: sa,
+                received: sb,
+            },
+            Leg {
+                intent: b,
+                spent: sb,
+                received: sa,
+            },
+        ));
+    }
+    // `a` fully filled, `b` keeps a remainder.
+    if partially_fillable(b) && sb > ma && mul_div_ceil(mb, ma, sb)? <= sa {
+        return Some((
+            Leg {
+                intent: a,
+                spent: sa,
+                received: ma,
+            },
+            Leg {
+                intent: b,
+                spent: ma,
+                received: sa,
+            },
+        ));
+    }
+    // `b` fully filled, `a` keeps a remainder.
+    if partially_fillable(a) && sa > mb && mul_div_ceil(ma, mb, sa)? <= sb {
+        return Some((
+            Leg {
+                intent: a,
+                spent: mb,
+                received: sb,
+            },
+            Leg {
+                intent: b,
+                spent: sb,
+                received: mb,
+            },
+        ));
+    }
+    None
+}
+
+/// Adds a matched pair to `builder`.
+fn add_pair(builder: &mut SolutionBuilder, a: Leg, b: Leg) -> Result<(), SolutionBuilderError> {
+    let in_a = builder.add_input(a.intent.clone());
+    let in_b = builder.add_input(b.intent.clone());
+    builder.deliver(in_b, in_a, a.received)?;
+    builder.deliver(in_a, in_b, b.received)?;
+    for (handle, leg) in [(in_a, &a), (in_b, &b)] {
+        if leg.spent < leg.intent.src_amount {
+            builder.remainder(handle, leg.spent)?;
+        }
+    }
+    Ok(())
+}
+
+/// Matches two opposing intents, if their prices cross.
+pub fn match_pair(a: &Intent, b: &Intent) -> Option<Solution> {
+    let (leg_a, leg_b) = legs(a, b)?;
+    let mut builder = Solution::builder();
+    add_pair(&mut builder, leg_a, leg_b).ok()?;
+    builder.build().ok()
+}
+
+/// Greedily pairs up the given intents and settles all matched pairs in one solution.
+///
+/// Pairs that would make the solution invalid together with the pairs already matched, e.g. by
+/// reusing an intent or a remainder nonce, are skipped. Returns `None` if no pair matches.
+pub fn match_intents(intents: &[Intent]) -> Option<Solution> {
+    let mut matched = vec![false; intents.len()];
+    let mut builder = Solution::builder();
+    let mut any = false;
+    for i in 0..intents.len() {
+        if matched[i] {
+            continue;
+        }
+        for j in i + 1..intents.len() {
+            if matched[j] {
+                continue;
+            }
+            let Some((leg_a, leg_b)) = legs(&intents[i], &intents[j]) else {
+                continue;
+            };
+            // Leave no half-added pair behind if it cannot be settled.
+            let mut with_pair = builder.clone();
+            if add_pair(&mut with_pair, leg_a, leg_b).is_ok() && with_pair.clone().build().is_ok() {
+                builder = with_pair;
+                any = true;
+                matched[i] = true;
+                matched[j] = true;
+                break;
+            }
+        }
+    }
+    if !any {
+        return None;
+    }
+    builder.build().ok()
+}
+
+/// A [`Strategy`] that matches every new intent against the open intents selling what it wants.
+pub struct CowStrategy;
+
+impl Strategy for CowStrategy {
+    async fn on_new_intent(&self, intent: &Intent, book: &OpenIntentBook) -> Option<Proposal> {
+        let intent_id = intent.intent_id();
+        book.filter(|other| {
+            intent.outcome.m_tokens.contains(&other.src_m_token)
+                && other.outcome.m_tokens.contains(&intent.src_m_token)
+        })
+        .iter()
+        .filter(|other| other.intent_id() != intent_id)
+        .find_map(|other| match_pair(intent, other))
+        .map(Proposal::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: Address = Address::repeat_byte(0xaa);
+    const ETH: Address = Address::repeat_byte(0xbb);
+
+    fn intent(author: u8, sell: Address, sell_amt: u64, buy: Address, buy_amt: u64) -> Intent {
+        Intent::simple_swap(
+            Address::repeat_byte(author),
+            U256::from(2_000_000_000_u64),
+            U256::ZERO,
+            None,
+            sell,
+            U256::from(sell_amt),
+            buy,
+            U256::from(buy_amt),
+        )
+    }
+
+    #[test]
+    fn test_full_match() {
+        let a = intent(1, USDC, 3000, ETH, 1);
+        let b = intent(2, ETH, 1, USDC, 2900);
+        let solution = match_pair(&a, &b).unwrap();
+        assert!(solution.intent_outputs.is_empty());
+        assert_eq!(solution.validate(&[a, b]), Ok(()));
+    }
+
+    #[test]
+    fn test_partial_match_leaves_remainder() {
+        let mut a = intent(1, USDC, 9000, ETH, 3);
+        a.outcome.fill_structure = FillStructure::PercentageFilled;
+        let b = intent(2, ETH, 1, USDC, 3000);
+        let solution = match_pair(&a, &b).unwrap();
+        assert_eq!(solution.intent_outputs.len(), 1);
+        let child = &solution.intent_outputs[0];
+        assert_eq!(child.src_amount, U256::from(6000));
+        assert_eq!(child.outcome.m_amounts, vec![U256::from(2)]);
+        assert_eq!(child.nonce, a.nonce + U256::from(1));
+        assert_eq!(solution.validate(&[a, b]), Ok(()));
+    }
+
+    #[test]
+    fn test_exact_intent_is_not_partially_filled() {
+        let a = intent(1, USDC, 9000, ETH, 3);
+        let b = intent(2, ETH, 1, USDC, 3000);
+        assert!(match_pair(&a, &b).is_none());
+    }
+
+    #[test]
+    fn test_match_intents_combines_pairs() {
+        let intents = vec![
+            intent(1, USDC, 3000, ETH, 1),
+            intent(3, USDC, 10, Address::repeat_byte(0xcc), 10),
+            intent(2, ETH, 1, USDC, 3000),
+            intent(4, ETH, 2, USDC, 6000),
+            intent(5, USDC, 6000, ETH, 2),
+        ];
+        let solution = match_intents(&intents).unwrap();
+        assert_eq!(solution.intent_ids.len(), 4);
+        assert_eq!(solution.validate(&intents), Ok(()));
+    }
+
+    #[test]
+    fn test_match_intents_skips_conflicting_pair() {
+        let mut a1 = intent(1, USDC, 9000, ETH, 3);
+        a1.outcome.fill_structure = FillStructure::PercentageFilled;
+        // Same author and nonce, so its remainder would clash with the one of `a1`.
+        let mut a2 = intent(1, USDC, 12000, ETH, 4);
+        a2.outcome.fill_structure = FillStructure::PercentageFilled;
+        let intents = vec![
+            a1,
+            intent(2, ETH, 1, USDC, 3000),
+            a2,
+            intent(3, ETH, 1, USDC, 3000),
+        ];
+        let solution = match_intents(&intents).unwrap();
+        assert_eq!(
+            solution.intent_ids,
+            vec![intents[0].intent_id(), intents[1].intent_id()]
+        );
+        assert_eq!(solution.validate(&intents), Ok(()));
+    }
+}
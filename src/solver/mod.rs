@@ -0,0 +1,415 @@
+pub mod cow;
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::StreamExt;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+use crate::client::medusa_ws::CloseReason;
+use crate::client::{MedusaWsClient, OpenIntentBook, create_medusa_ws_client};
+use crate::error::Error;
+use crate::types::intents::{Intent, IntentId, IntentState, SignedIntent};
+use crate::types::refinement::RefinementStatus;
+use crate::types::rpc_payloads::SignedAddSolver;
+use crate::types::solution::{SignedSolution, Solution};
+use crate::types::ws::WsBroadcastMessage;
+
+/// A solution a strategy wants to propose, with any intents the solver publishes alongside it.
+#[derive(Debug, Clone)]
+pub struct Proposal {
+    pub solution: Solution,
+    pub intents: Vec<SignedIntent>,
+    pub complementary_withdrawal: bool,
+}
+
+impl From<Solution> for Proposal {
+    fn from(solution: Solution) -> Self {
+        Self {
+            solution,
+            intents: vec![],
+            complementary_withdrawal: false,
+        }
+    }
+}
+
+/// The decision logic of a solver. The [`SolverRunner`] takes care of the plumbing.
+pub trait Strategy: Send + Sync + 'static {
+    /// Called for every new open intent the solver has not proposed on yet. `book` holds all
+    /// currently open intents, including `intent`.
+    fn on_new_intent(
+        &self,
+        intent: &Intent,
+        book: &OpenIntentBook,
+    ) -> impl Future<Output = Option<Proposal>> + Send;
+
+    /// Called when Medusa asks solvers to refine an intent. Returning `None` sends no answer.
+    fn on_refinement_needed(
+        &self,
+        _intent: &Intent,
+    ) -> impl Future<Output = Option<RefinementStatus>> + Send {
+        async { None }
+    }
+
+    /// Called when Medusa rejects a solution proposed by this solver. Its intents become
+    /// eligible for new proposals again.
+    fn on_solution_rejected(&self, _solution: &SignedSolution) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SolverError {
+    #[error("max_concurrency must be between 1 and {max}, got {got}")]
+    InvalidMaxConcurrency { got: usize, max: usize },
+    #[error("failed to connect to Medusa: {0}")]
+    Connect(#[source] Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct SolverConfig {
+    /// Maximum number of strategy callbacks running at the same time.
+    pub max_concurrency: usize,
+    /// Minimum time between two proposals.
+    pub min_proposal_interval: Duration,
+}
+
+impl SolverConfig {
+    fn validate(&self) -> Result<(), SolverError> {
+        if !(1..=Semaphore::MAX_PERMITS).contains(&self.max_concurrency) {
+            return Err(SolverError::InvalidMaxConcurrency {
+                got: self.max_concurrency,
+                max: Semaphore::MAX_PERMITS,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 16,
+            min_proposal_interval: Duration::ZERO,
+        }
+    }
+}
+
+/// Runs a [`Strategy`] against Medusa: feeds it intents, signs and proposes its solutions.
+pub struct SolverRunner<St, Sg> {
+    strategy: Arc<St>,
+    signer: Arc<Sg>,
+    client: Arc<MedusaWsClient>,
+    book: OpenIntentBook,
+    config: SolverConfig,
+    proposed: Arc<Mutex<HashSet<IntentId>>>,
+    last_proposal: Arc<tokio::sync::Mutex<Option<Instant>>>,
+}
+
+impl<St, Sg> SolverRunner<St, Sg>
+where
+    St: Strategy,
+    Sg: alloy::signers::Signer + Send + Sync + 'static,
+{
+    /// Fails if `config` is invalid.
+    pub fn new(
+        client: MedusaWsClient,
+        signer: Sg,
+        strategy: St,
+        config: SolverConfig,
+    ) -> Result<Self, SolverError> {
+        config.validate()?;
+        Ok(Self {
+            strategy: Arc::new(strategy),
+            signer: Arc::new(signer),
+            client: Arc::new(client),
+            book: OpenIntentBook::new(),
+            config,
+            proposed: Default::default(),
+            last_proposal: Default::default(),
+        })
+    }
+
+    /// Connects to Medusa with [`create_medusa_ws_client`] and builds a runner on top of it.
+    pub async fn connect(
+        url: String,
+        signed_add_solver: SignedAddSolver,
+        signer: Sg,
+        strategy: St,
+        config: SolverConfig,
+    ) -> Result<Self, SolverError> {
+        config.validate()?;
+        let client = create_medusa_ws_client(url, signed_add_solver)
+            .await
+            .map_err(SolverError::Connect)?;
+        Self::new(client, signer, strategy, config)
+    }
+
+    pub fn client(&self) -> &MedusaWsClient {
+        &self.client
+    }
+
+    pub fn book(&self) -> &OpenIntentBook {
+        &self.book
+    }
+
+    /// Processes broadcasts until the connection ends, then returns why it did.
+    ///
+    /// Strategy callbacks run in their own tasks, at most `max_concurrency` at a time. Waiting
+    /// callbacks queue up without holding up the intake of broadcasts.
+    pub async fn run(self) -> CloseReason {
+        let mut messages = std::pin::pin!(self.client.subscribe());
+        let permits = Arc::new(Semaphore::new(self.config.max_concurrency));
+        let mut tasks = JoinSet::new();
+        if let Err(e) = self.client.request_open_intents().await {
+            tracing::error!("Failed to request open intents: {}", e);
+        }
+
+        while let Some(message) = messages.next().await {
+            while tasks.try_join_next().is_some() {}
+            self.book.apply(&message);
+            match message {
+                WsBroadcastMessage::ExistingOpenIntents(intents) => {
+                    for intent in intents {
+                        self.spawn_on_new_intent(intent, &permits, &mut tasks);
+                    }
+                }
+                WsBroadcastMessage::NewIntent(intent) => {
+                    self.spawn_on_new_intent(intent, &permits, &mut tasks);
+                }
+                WsBroadcastMessage::RefinementNeededForIntent(intent) => {
+                    let permits = permits.clone();
+                    let strategy = self.strategy.clone();
+                    let client = self.client.clone();
+                    tasks.spawn(async move {
+                        let _permit = permits.acquire_owned().await;
+                        let Some(refinement) = strategy.on_refinement_needed(&intent).await else {
+                            return;
+                        };
+                        if let Err(e) = client.send_refinement(intent.intent_id(), refinement).await
+                        {
+                            tracing::error!("Failed to send refinement: {}", e);
+                        }
+                    });
+                }
+                WsBroadcastMessage::SolutionRejected(solution) => {
+                    if solution.try_recover_address() != Some(self.signer.address()) {
+                        continue;
+                    }
+                    self.forget_proposed(&solution.solution.intent_ids);
+                    let permits = permits.clone();
+                    let strategy = self.strategy.clone();
+                    tasks.spawn(async move {
+                        let _permit = permits.acquire_owned().await;
+                        strategy.on_solution_rejected(&solution).await;
+                    });
+                }
+                WsBroadcastMessage::IntentsSolved(intent_ids, _) => {
+                    self.forget_proposed(&intent_ids);
+                }
+                // Cancelled, expired and failed intents will not be solved by our proposal.
+                WsBroadcastMessage::IntentStatusUpdated(intent_id, state) => {
+                    if state != IntentState::Open {
+                        self.forget_proposed(&[intent_id]);
+                    }
+                }
+                WsBroadcastMessage::Solutions(..) => {}
+            }
+        }
+
+        // Let in-flight callbacks finish before closing the connection. Once they are joined
+        // this runner holds the only reference to the client.
+        while tasks.join_next().await.is_some() {}
+        match Arc::into_inner(self.client) {
+            Some(client) => client.shutdown().await,
+            None => unreachable!("all tasks sharing the client have been joined"),
+        }
+    }
+
+    fn forget_proposed(&self, intent_ids: &[IntentId]) {
+        let mut proposed = self.proposed.lock().unwrap();
+        for intent_id in intent_ids {
+            proposed.remove(intent_id);
+        }
+    }
+
+    fn spawn_on_new_intent(
+        &self,
+        intent: Intent,
+        permits: &Arc<Semaphore>,
+        tasks: &mut JoinSet<()>,
+    ) {
+        if self.proposed.lock().unwrap().contains(&intent.intent_id()) {
+            return;
+        }
+        let permits = permits.clone();
+        let strategy = self.strategy.clone();
+        let signer = self.signer.clone();
+        let client = self.client.clone();
+        let book = self.book.clone();
+        let proposed = self.proposed.clone();
+        let last_proposal = self.last_proposal.clone();
+        let min_interval = self.config.min_proposal_interval;
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let Some(proposal) = strategy.on_new_intent(&intent, &book).await else {
+                return;
+            };
+
+            let mut inputs: Vec<Intent> = proposal
+                .intents
+                .iter()
+                .map(|signed| signed.intent.clone())
+                .collect();
+            inputs.extend(
+                proposal
+                    .solution
+                    .intent_ids
+                    .iter()
+                    .filter_map(|intent_id| book.get(intent_id)),
+            );
+            if let Err(e) = proposal.solution.validate(&inputs) {
+                tracing::warn!("Strategy produced an invalid solution: {}", e);
+                return;
+            }
+            {
+                let mut proposed = proposed.lock().unwrap();
+                if proposal
+                    .solution
+                    .intent_ids
+                    .iter()
+                    .any(|intent_id| proposed.contains(intent_id))
+                {
+                    tracing::debug!("Skipping solution overlapping an earlier proposal");
+                    return;
+                }
+                proposed.extend(proposal.solution.intent_ids.iter().copied());
+            }
+
+            {
+                let mut last_proposal = last_proposal.lock().await;
+                if let Some(last) = *last_proposal {
+                    tokio::time::sleep_until(last + min_interval).await;
+                }
+                *last_proposal = Some(Instant::now());
+            }
+            let signed = proposal.solution.sign(signer.as_ref()).await;
+            if let Err(e) = client
+                .propose_solution(signed, proposal.intents, proposal.complementary_withdrawal)
+                .await
+            {
+                tracing::error!("Failed to propose solution: {}", e);
+                let mut proposed = proposed.lock().unwrap();
+                for intent_id in &proposal.solution.intent_ids {
+                    proposed.remove(intent_id);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use alloy::primitives::{Address, U256};
+    use alloy::signers::local::PrivateKeySigner;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::mock::MockMedusaWs;
+    use crate::test_support::{add_solver, test_deployment};
+    use crate::types::ws::WsPayload;
+
+    /// Fills every intent that sells and buys the same mToken by paying it back to its author.
+    struct Refund {
+        rejected: mpsc::UnboundedSender<SignedSolution>,
+    }
+
+    impl Strategy for Refund {
+        async fn on_new_intent(&self, intent: &Intent, _book: &OpenIntentBook) -> Option<Proposal> {
+            let mut builder = Solution::builder();
+            let input = builder.add_input(intent.clone());
+            builder.deliver(input, input, intent.src_amount).ok()?;
+            Some(builder.build().unwrap().into())
+        }
+
+        async fn on_solution_rejected(&self, solution: &SignedSolution) {
+            let _ = self.rejected.send(solution.clone());
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(SolverConfig::default().validate().is_ok());
+        let invalid = SolverConfig {
+            max_concurrency: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            invalid.validate(),
+            Err(SolverError::InvalidMaxConcurrency { got: 0, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_propose_and_repropose_after_rejection() {
+        let server = MockMedusaWs::start(test_deployment()).await.unwrap();
+        let signer = PrivateKeySigner::random();
+        let add_solver = add_solver(&signer).await;
+        let client = create_medusa_ws_client(server.url(), add_solver)
+            .await
+            .unwrap();
+        server.wait_for_sessions(1).await;
+        let (rejected_send, mut rejected) = mpsc::unbounded_channel();
+        let strategy = Refund {
+            rejected: rejected_send,
+        };
+        let runner =
+            SolverRunner::new(client, signer.clone(), strategy, Default::default()).unwrap();
+
+        let mut payloads = Box::pin(server.payloads());
+        let runner = tokio::spawn(runner.run());
+        let mut next_proposal = async || loop {
+            if let Some(WsPayload::ProposeSolution(solution, ..)) = payloads.next().await {
+                return solution;
+            }
+        };
+
+        let usdc = Address::repeat_byte(0xaa);
+        let intent = Intent::simple_swap(
+            Address::repeat_byte(1),
+            U256::from(u64::MAX),
+            U256::ZERO,
+            None,
+            usdc,
+            U256::from(100),
+            usdc,
+            U256::from(100),
+        );
+        server.broadcast(WsBroadcastMessage::NewIntent(intent.clone()));
+        let proposal = next_proposal().await;
+        assert_eq!(proposal.solution.intent_ids, vec![intent.intent_id()]);
+        assert_eq!(proposal.try_recover_address(), Some(signer.address()));
+
+        // Once rejected, the intent can be proposed on again.
+        server.broadcast(WsBroadcastMessage::SolutionRejected(proposal.clone()));
+        assert_eq!(
+            rejected.recv().await.unwrap().solution.intent_ids,
+            proposal.solution.intent_ids
+        );
+        server.broadcast(WsBroadcastMessage::NewIntent(intent.clone()));
+        assert_eq!(
+            next_proposal().await.solution.intent_ids,
+            vec![intent.intent_id()]
+        );
+
+        server.drop_connections();
+        assert!(matches!(
+            runner.await.unwrap(),
+            CloseReason::ConnectionLost(_)
+        ));
+    }
+}
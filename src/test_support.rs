@@ -0,0 +1,75 @@
+//! Fixtures shared by the unit tests.
+
+use alloy::primitives::Address;
+
+use crate::types::deployment::ArcadiaDeployment;
+
+/// The deployment every test signs against.
+pub(crate) fn test_deployment() -> ArcadiaDeployment {
+    ArcadiaDeployment {
+        chain_id: 1,
+        intent_book: Address::repeat_byte(0x11),
+        mtoken_manager: Address::repeat_byte(0x22),
+        teller: Address::repeat_byte(0x33),
+    }
+}
+
+#[cfg(feature = "mock")]
+pub(crate) use mock::*;
+
+#[cfg(feature = "mock")]
+mod mock {
+    use alloy::signers::local::PrivateKeySigner;
+    use jsonrpsee::{http_client::HttpClient, server::ServerHandle};
+
+    use super::test_deployment;
+    use crate::{
+        client::{MedusaRpcClient, create_medusa_rpc_client},
+        mock::MockMedusa,
+        types::{
+            intent_builder::IntentBuilder,
+            intents::{IntentId, SignedIntent},
+            rpc_payloads::{AddSolver, SignedPayload},
+        },
+    };
+
+    /// Serves a [`MockMedusa`] for [`test_deployment`] over HTTP and connects to it.
+    pub(crate) async fn start_mock_medusa() -> (MockMedusa, HttpClient, ServerHandle) {
+        let medusa = MockMedusa::new(test_deployment());
+        let (addr, handle) = medusa
+            .clone()
+            .start("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let client = create_medusa_rpc_client(format!("http://{addr}")).unwrap();
+        (medusa, client, handle)
+    }
+
+    /// Fetches the next nonce for `builder`, signs the intent and proposes it.
+    pub(crate) async fn propose_intent(
+        client: &impl MedusaRpcClient,
+        signer: &PrivateKeySigner,
+        builder: IntentBuilder,
+    ) -> (SignedIntent, IntentId) {
+        let signed = builder
+            .fetch_nonce(client)
+            .await
+            .unwrap()
+            .sign(signer, &test_deployment())
+            .await
+            .unwrap();
+        let (_, intent_id) = client.propose_intent(signed.clone()).await.unwrap();
+        (signed, intent_id)
+    }
+
+    /// Signs an [`AddSolver`] payload authenticating `signer`.
+    pub(crate) async fn add_solver(signer: &PrivateKeySigner) -> SignedPayload<AddSolver> {
+        AddSolver {
+            address: signer.address(),
+            nonce: alloy::primitives::U256::from(1),
+        }
+        .sign(signer, &test_deployment())
+        .await
+        .unwrap()
+    }
+}
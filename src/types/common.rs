@@ -1,18 +1,225 @@
 pub use alloy::primitives::{Address, B256, ChainId, U256};
 pub use alloy::signers::Signer;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 pub type BlockTime = U256;
 pub type RealTime = u64;
 pub type MetaTokenId = u32;
 pub type IntentId = B256;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Decimals of every mToken.
+pub const MTOKEN_DECIMALS: u8 = 18;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TokenAmt {
     pub amt: U256,
     pub decimals: u8,
 }
 
+/// How to round when an amount loses precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest value, ties away from zero.
+    HalfUp,
+    /// Fail with [`TokenAmtError::Inexact`] instead of rounding.
+    Exact,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TokenAmtError {
+    #[error("invalid amount: {0:?}")]
+    InvalidAmount(String),
+    #[error("amount {amount:?} has more than {decimals} decimals")]
+    TooManyDecimals { amount: String, decimals: u8 },
+    #[error("amounts have different decimals: {0} and {1}")]
+    DecimalsMismatch(u8, u8),
+    #[error("amount overflow")]
+    Overflow,
+    #[error("amount underflow")]
+    Underflow,
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("rescaling from {from} to {to} decimals loses precision")]
+    Inexact { from: u8, to: u8 },
+}
+
+fn pow10(exp: u8) -> Option<U256> {
+    U256::from(10).checked_pow(U256::from(exp))
+}
+
+impl TokenAmt {
+    pub fn new(amt: U256, decimals: u8) -> Self {
+        Self { amt, decimals }
+    }
+
+    pub fn zero(decimals: u8) -> Self {
+        Self::new(U256::ZERO, decimals)
+    }
+
+    /// Parses a human-readable amount such as `"12.5"` into base units with `decimals` decimals.
+    pub fn parse(amount: &str, decimals: u8) -> Result<Self, TokenAmtError> {
+        let invalid = || TokenAmtError::InvalidAmount(amount.to_string());
+        let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > decimals as usize {
+            return Err(TokenAmtError::TooManyDecimals {
+                amount: amount.to_string(),
+                decimals,
+            });
+        }
+        let parse = |digits: &str| -> Result<U256, TokenAmtError> {
+            if digits.is_empty() {
+                return Ok(U256::ZERO);
+            }
+            U256::from_str_radix(digits, 10).map_err(|_| TokenAmtError::Overflow)
+        };
+        let scale = pow10(decimals).ok_or(TokenAmtError::Overflow)?;
+        let fraction_scale =
+            pow10(decimals - fraction.len() as u8).ok_or(TokenAmtError::Overflow)?;
+        let (whole, fraction) = (parse(whole)?, parse(fraction)? * fraction_scale);
+        let amt = whole
+            .checked_mul(scale)
+            .and_then(|whole| whole.checked_add(fraction))
+            .ok_or(TokenAmtError::Overflow)?;
+        Ok(Self::new(amt, decimals))
+    }
+
+    /// Converts the amount to `decimals` decimals, rounding as `rounding` says if precision
+    /// is lost.
+    pub fn rescale(&self, decimals: u8, rounding: RoundingMode) -> Result<Self, TokenAmtError> {
+        if decimals >= self.decimals {
+            let amt = pow10(decimals - self.decimals)
+                .and_then(|scale| self.amt.checked_mul(scale))
+                .ok_or(TokenAmtError::Overflow)?;
+            return Ok(Self::new(amt, decimals));
+        }
+        let (quotient, remainder, half_or_more) = match pow10(self.decimals - decimals) {
+            Some(scale) => {
+                let (q, r) = self.amt.div_rem(scale);
+                (q, r, !r.is_zero() && r >= scale - r)
+            }
+            // The divisor exceeds any U256, so everything is remainder and less than half.
+            None => (U256::ZERO, self.amt, false),
+        };
+        let round_up = match rounding {
+            RoundingMode::Down => false,
+            RoundingMode::Up => !remainder.is_zero(),
+            RoundingMode::HalfUp => half_or_more,
+            RoundingMode::Exact if remainder.is_zero() => false,
+            RoundingMode::Exact => {
+                return Err(TokenAmtError::Inexact {
+                    from: self.decimals,
+                    to: decimals,
+                });
+            }
+        };
+        let amt = if round_up {
+            quotient
+                .checked_add(U256::from(1))
+                .ok_or(TokenAmtError::Overflow)?
+        } else {
+            quotient
+        };
+        Ok(Self::new(amt, decimals))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Result<Self, TokenAmtError> {
+        self.same_decimals(other)?;
+        let amt = self
+            .amt
+            .checked_add(other.amt)
+            .ok_or(TokenAmtError::Overflow)?;
+        Ok(Self::new(amt, self.decimals))
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, TokenAmtError> {
+        self.same_decimals(other)?;
+        let amt = self
+            .amt
+            .checked_sub(other.amt)
+            .ok_or(TokenAmtError::Underflow)?;
+        Ok(Self::new(amt, self.decimals))
+    }
+
+    /// Computes `self * numerator / denominator`, e.g. to apply a price or a fee.
+    pub fn checked_mul_div(
+        &self,
+        numerator: U256,
+        denominator: U256,
+        rounding: RoundingMode,
+    ) -> Result<Self, TokenAmtError> {
+        if denominator.is_zero() {
+            return Err(TokenAmtError::DivisionByZero);
+        }
+        let product = self
+            .amt
+            .checked_mul(numerator)
+            .ok_or(TokenAmtError::Overflow)?;
+        let (quotient, remainder) = product.div_rem(denominator);
+        let round_up = match rounding {
+            RoundingMode::Down => false,
+            RoundingMode::Up => !remainder.is_zero(),
+            RoundingMode::HalfUp => !remainder.is_zero() && remainder >= denominator - remainder,
+            RoundingMode::Exact if remainder.is_zero() => false,
+            RoundingMode::Exact => {
+                return Err(TokenAmtError::Inexact {
+                    from: self.decimals,
+                    to: self.decimals,
+                });
+            }
+        };
+        let amt = if round_up {
+            quotient + U256::from(1)
+        } else {
+            quotient
+        };
+        Ok(Self::new(amt, self.decimals))
+    }
+
+    fn same_decimals(&self, other: &Self) -> Result<(), TokenAmtError> {
+        if self.decimals != other.decimals {
+            return Err(TokenAmtError::DecimalsMismatch(
+                self.decimals,
+                other.decimals,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Formats the amount in human-readable units, e.g. `12.5`. A precision (`{:.2}`) pads or
+/// truncates the fractional part.
+impl fmt::Display for TokenAmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.amt.to_string();
+        let decimals = self.decimals as usize;
+        let padded = format!("{digits:0>width$}", width = decimals + 1);
+        let (whole, fraction) = padded.split_at(padded.len() - decimals);
+        let fraction = match f.precision() {
+            Some(precision) => format!("{:0<precision$.precision$}", fraction),
+            None => fraction.trim_end_matches('0').to_string(),
+        };
+        if fraction.is_empty() {
+            write!(f, "{whole}")
+        } else {
+            write!(f, "{whole}.{fraction}")
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TokenAmtRange {
     from: TokenAmt,
@@ -29,3 +236,49 @@ pub struct TokenInfo {
     contract_address: Address,
     chain: ChainId,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        let amt = TokenAmt::parse("12.5", 6).unwrap();
+        assert_eq!(amt.amt, U256::from(12_500_000));
+        assert_eq!(amt.to_string(), "12.5");
+        assert_eq!(format!("{amt:.2}"), "12.50");
+        assert_eq!(TokenAmt::parse(".05", 2).unwrap().to_string(), "0.05");
+        assert_eq!(TokenAmt::parse("7", 0).unwrap().to_string(), "7");
+        assert!(matches!(
+            TokenAmt::parse("1.0000001", 6),
+            Err(TokenAmtError::TooManyDecimals { .. })
+        ));
+        assert!(TokenAmt::parse("1.2.3", 6).is_err());
+        assert!(TokenAmt::parse("-1", 6).is_err());
+    }
+
+    #[test]
+    fn test_rescale_rounding() {
+        let amt = TokenAmt::new(U256::from(1_500_000_000_001_u64), 18);
+        let down = amt.rescale(6, RoundingMode::Down).unwrap();
+        assert_eq!(down.amt, U256::from(1));
+        assert_eq!(amt.rescale(6, RoundingMode::Up).unwrap().amt, U256::from(2));
+        assert_eq!(
+            amt.rescale(6, RoundingMode::HalfUp).unwrap().amt,
+            U256::from(2)
+        );
+        assert!(amt.rescale(6, RoundingMode::Exact).is_err());
+        assert_eq!(
+            down.rescale(18, RoundingMode::Exact).unwrap().amt,
+            U256::from(1_000_000_000_000_u64)
+        );
+        assert_eq!(
+            TokenAmt::new(U256::from(1), 6).checked_add(&down),
+            Ok(TokenAmt::new(U256::from(2), 6))
+        );
+        assert_eq!(
+            amt.checked_sub(&down),
+            Err(TokenAmtError::DecimalsMismatch(18, 6))
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use alloy::primitives::{Address, ChainId};
+use alloy::sol_types::Eip712Domain;
+use serde::{Deserialize, Serialize};
+
+use super::sol_types::{fast_withdrawal_domain, intent_domain, teller_domain};
+
+/// The Arcadia contracts a client talks to. Every EIP-712 domain the SDK signs under is derived
+/// from here, so payloads cannot be signed for the wrong chain or contract.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArcadiaDeployment {
+    pub chain_id: ChainId,
+    pub intent_book: Address,
+    pub mtoken_manager: Address,
+    pub teller: Address,
+}
+
+impl ArcadiaDeployment {
+    /// See [`intent_domain`].
+    pub fn intent_domain(&self) -> Eip712Domain {
+        intent_domain(self.chain_id, self.intent_book)
+    }
+
+    /// See [`fast_withdrawal_domain`].
+    pub fn fast_withdrawal_domain(&self) -> Eip712Domain {
+        fast_withdrawal_domain(self.chain_id, self.mtoken_manager)
+    }
+
+    /// Domain of the Medusa payloads tied to intents: cancellations, solver registration and
+    /// mToken withdrawals. The same as [`Self::intent_domain`].
+    pub fn payload_domain(&self) -> Eip712Domain {
+        self.intent_domain()
+    }
+
+    /// See [`teller_domain`].
+    pub fn teller_domain(&self) -> Eip712Domain {
+        teller_domain(self.chain_id, self.teller)
+    }
+}
@@ -0,0 +1,421 @@
+use std::time::Duration;
+
+use alloy::primitives::{Address, U256};
+use alloy::signers::Signer;
+
+use super::deployment::ArcadiaDeployment;
+use super::intent_validator::IntentValidationError;
+use super::intents::{
+    FillStructure, Intent, Outcome, OutcomeAssetStructure, SignedIntent, current_timestamp_sec,
+};
+use super::sol_types::CrossChainIntent;
+use super::token::Token;
+use super::token_registry::TokenRegistryError;
+use crate::client::MedusaRpcClient;
+use crate::error::MedusaError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IntentBuilderError {
+    #[error("missing field: {0}")]
+    MissingField(&'static str),
+    #[error("invalid intent: {0}")]
+    Invalid(#[from] IntentValidationError),
+    #[error("failed to fetch nonce: {0}")]
+    Rpc(#[from] MedusaError),
+    #[error("the author's nonce cannot be incremented")]
+    NonceOverflow,
+    #[error(transparent)]
+    Token(#[from] TokenRegistryError),
+}
+
+/// When the intent stops being valid.
+#[derive(Debug, Clone, Copy)]
+enum Expiry {
+    At(U256),
+    /// Relative to `valid_after`, or to the build time if that is later.
+    After(Duration),
+}
+
+/// Builds an [`Intent`] step by step and checks it before it is signed.
+///
+/// ```ignore
+/// let signed = IntentBuilder::new(author)
+///     .sell(m_usdc, usdc_amount)
+///     .receive(m_eth, eth_amount)
+///     .receive(m_weth, eth_amount)
+///     .outcome_asset_structure(OutcomeAssetStructure::AnySingle)
+///     .fill_structure(FillStructure::Minimum)
+///     .valid_for(Duration::from_secs(600))
+///     .fetch_nonce(&medusa_client)
+///     .await?
+///     .sign(&signer, &deployment)
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct IntentBuilder {
+    author: Address,
+    src: Option<(Address, U256)>,
+    m_tokens: Vec<Address>,
+    m_amounts: Vec<U256>,
+    outcome_asset_structure: OutcomeAssetStructure,
+    fill_structure: FillStructure,
+    valid_after: U256,
+    expiry: Option<Expiry>,
+    nonce: Option<U256>,
+    /// The author's current nonce, if fetched from Medusa.
+    current_nonce: Option<U256>,
+}
+
+impl IntentBuilder {
+    /// Starts an `AnySingle`/`Exact` intent that is valid immediately.
+    pub fn new(author: Address) -> Self {
+        Self {
+            author,
+            src: None,
+            m_tokens: vec![],
+            m_amounts: vec![],
+            outcome_asset_structure: OutcomeAssetStructure::AnySingle,
+            fill_structure: FillStructure::Exact,
+            valid_after: U256::ZERO,
+            expiry: None,
+            nonce: None,
+            current_nonce: None,
+        }
+    }
+
+    /// Spends `amount` of `m_token`.
+    pub fn sell(mut self, m_token: Address, amount: U256) -> Self {
+        self.src = Some((m_token, amount));
+        self
+    }
+
+    /// Spends `amount` of `token`'s mToken, in 18 decimals.
+    pub fn sell_token(self, token: &Token, amount: U256) -> Self {
+        self.sell(token.mtoken_address, amount)
+    }
+
+    /// Adds `amount` of `m_token` to the outcome.
+    pub fn receive(mut self, m_token: Address, amount: U256) -> Self {
+        self.m_tokens.push(m_token);
+        self.m_amounts.push(amount);
+        self
+    }
+
+    /// Adds `amount` of `token`'s mToken, in 18 decimals, to the outcome.
+    pub fn receive_token(self, token: &Token, amount: U256) -> Self {
+        self.receive(token.mtoken_address, amount)
+    }
+
+    pub fn outcome_asset_structure(
+        mut self,
+        outcome_asset_structure: OutcomeAssetStructure,
+    ) -> Self {
+        self.outcome_asset_structure = outcome_asset_structure;
+        self
+    }
+
+    pub fn fill_structure(mut self, fill_structure: FillStructure) -> Self {
+        self.fill_structure = fill_structure;
+        self
+    }
+
+    /// Unix timestamp (seconds) from which the intent can be filled.
+    pub fn valid_after(mut self, valid_after: U256) -> Self {
+        self.valid_after = valid_after;
+        self
+    }
+
+    /// Unix timestamp (seconds) until which the intent can be filled.
+    pub fn valid_before(mut self, valid_before: U256) -> Self {
+        self.expiry = Some(Expiry::At(valid_before));
+        self
+    }
+
+    /// Keeps the intent valid for `duration` from `valid_after`, or from when it is built if
+    /// that is later.
+    pub fn valid_for(mut self, duration: Duration) -> Self {
+        self.expiry = Some(Expiry::After(duration));
+        self
+    }
+
+    pub fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Uses the nonce after the author's current one, as reported by Medusa.
+    pub async fn fetch_nonce(
+        self,
+        client: &impl MedusaRpcClient,
+    ) -> Result<Self, IntentBuilderError> {
+        let current_nonce = client
+            .get_nonce(self.author)
+            .await
+            .map_err(MedusaError::from)?;
+        let nonce = current_nonce
+            .checked_add(U256::from(1))
+            .ok_or(IntentBuilderError::NonceOverflow)?;
+        let mut builder = self.nonce(nonce);
+        builder.current_nonce = Some(current_nonce);
+        Ok(builder)
+    }
+
+    pub fn build(&self) -> Result<Intent, IntentBuilderError> {
+        self.build_at(current_timestamp_sec())
+    }
+
+    /// Builds the intent as if the current time were `now` (unix seconds) and checks it with
+    /// [`Intent::validate`].
+    pub fn build_at(&self, now: u64) -> Result<Intent, IntentBuilderError> {
+        let (src_m_token, src_amount) = self.src.ok_or(IntentBuilderError::MissingField("src"))?;
+        let nonce = self
+            .nonce
+            .ok_or(IntentBuilderError::MissingField("nonce"))?;
+        let valid_before = match self.expiry {
+            Some(Expiry::At(valid_before)) => valid_before,
+            Some(Expiry::After(duration)) => {
+                self.valid_after.max(U256::from(now)) + U256::from(duration.as_secs())
+            }
+            None => return Err(IntentBuilderError::MissingField("valid_before")),
+        };
+        let intent = Intent {
+            author: self.author,
+            valid_before,
+            valid_after: self.valid_after,
+            nonce,
+            src_m_token,
+            src_amount,
+            outcome: Outcome {
+                m_tokens: self.m_tokens.clone(),
+                m_amounts: self.m_amounts.clone(),
+                outcome_asset_structure: self.outcome_asset_structure.clone(),
+                fill_structure: self.fill_structure.clone(),
+            },
+        };
+        intent.validate(now, self.current_nonce.unwrap_or_default())?;
+        Ok(intent)
+    }
+
+    /// Builds the intent and signs it.
+    pub async fn sign<S: Signer>(
+        &self,
+        signer: &S,
+        deployment: &ArcadiaDeployment,
+    ) -> Result<SignedIntent, IntentBuilderError> {
+        let intent = self.build()?;
+        Ok(intent.sign(signer, deployment).await)
+    }
+}
+
+/// Builds a [`CrossChainIntent`]: sell an mToken on Arcadia for a token and/or native currency
+/// on the destination chain.
+#[derive(Debug, Clone)]
+pub struct CrossChainIntentBuilder {
+    author: Address,
+    src: Option<(Address, U256)>,
+    destination_chain_id: Option<u32>,
+    native_outcome: U256,
+    outcome: Option<(Address, U256)>,
+    expiry: Option<Expiry>,
+    nonce: Option<U256>,
+    current_nonce: Option<U256>,
+}
+
+impl CrossChainIntentBuilder {
+    pub fn new(author: Address) -> Self {
+        Self {
+            author,
+            src: None,
+            destination_chain_id: None,
+            native_outcome: U256::ZERO,
+            outcome: None,
+            expiry: None,
+            nonce: None,
+            current_nonce: None,
+        }
+    }
+
+    /// Spends `amount` of `m_token`.
+    pub fn sell(mut self, m_token: Address, amount: U256) -> Self {
+        self.src = Some((m_token, amount));
+        self
+    }
+
+    pub fn destination_chain(mut self, chain_id: u32) -> Self {
+        self.destination_chain_id = Some(chain_id);
+        self
+    }
+
+    /// Native currency to receive on the destination chain, in wei.
+    pub fn native_outcome(mut self, amount: U256) -> Self {
+        self.native_outcome = amount;
+        self
+    }
+
+    /// `amount` of `token` (on the destination chain) to receive.
+    pub fn receive(mut self, token: Address, amount: U256) -> Self {
+        self.outcome = Some((token, amount));
+        self
+    }
+
+    /// Receives `amount` of `token`'s spoke token and sets the destination chain accordingly.
+    ///
+    /// Fails if the token's spoke chain id does not fit in a `u32`.
+    pub fn receive_token(self, token: &Token, amount: U256) -> Result<Self, IntentBuilderError> {
+        let chain_id = token.spoke_chain_id_u32()?;
+        Ok(self
+            .destination_chain(chain_id)
+            .receive(token.spoke_address, amount))
+    }
+
+    pub fn valid_before(mut self, valid_before: U256) -> Self {
+        self.expiry = Some(Expiry::At(valid_before));
+        self
+    }
+
+    /// Keeps the intent valid for `duration` from when it is built.
+    pub fn valid_for(mut self, duration: Duration) -> Self {
+        self.expiry = Some(Expiry::After(duration));
+        self
+    }
+
+    pub fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Uses the nonce after the author's current one, as reported by Medusa.
+    pub async fn fetch_nonce(
+        self,
+        client: &impl MedusaRpcClient,
+    ) -> Result<Self, IntentBuilderError> {
+        let current_nonce = client
+            .get_nonce(self.author)
+            .await
+            .map_err(MedusaError::from)?;
+        let nonce = current_nonce
+            .checked_add(U256::from(1))
+            .ok_or(IntentBuilderError::NonceOverflow)?;
+        let mut builder = self.nonce(nonce);
+        builder.current_nonce = Some(current_nonce);
+        Ok(builder)
+    }
+
+    pub fn build(&self) -> Result<CrossChainIntent, IntentBuilderError> {
+        self.build_at(current_timestamp_sec())
+    }
+
+    /// Builds the intent as if the current time were `now` (unix seconds) and checks it with
+    /// [`CrossChainIntent::validate`].
+    pub fn build_at(&self, now: u64) -> Result<CrossChainIntent, IntentBuilderError> {
+        let (src_m_token, src_amount) = self.src.ok_or(IntentBuilderError::MissingField("src"))?;
+        let destination_chain_id = self
+            .destination_chain_id
+            .ok_or(IntentBuilderError::MissingField("destination_chain_id"))?;
+        let nonce = self
+            .nonce
+            .ok_or(IntentBuilderError::MissingField("nonce"))?;
+        let valid_before = match self.expiry {
+            Some(Expiry::At(valid_before)) => valid_before,
+            Some(Expiry::After(duration)) => U256::from(now) + U256::from(duration.as_secs()),
+            None => return Err(IntentBuilderError::MissingField("valid_before")),
+        };
+        let (outcome_token, outcome_amount) = self.outcome.unwrap_or_default();
+        let intent = CrossChainIntent {
+            author: self.author,
+            validBefore: valid_before,
+            nonce,
+            srcMToken: src_m_token,
+            srcAmount: src_amount,
+            destinationChainId: destination_chain_id,
+            nativeOutcome: self.native_outcome,
+            outcomeToken: outcome_token,
+            outcomeAmount: outcome_amount,
+        };
+        intent.validate(now, self.current_nonce.unwrap_or_default())?;
+        Ok(intent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_deployment;
+
+    #[test]
+    fn test_build_relative_window() {
+        let builder = IntentBuilder::new(Address::repeat_byte(1))
+            .sell(Address::repeat_byte(0xaa), U256::from(100))
+            .receive(Address::repeat_byte(0xbb), U256::from(1))
+            .receive(Address::repeat_byte(0xcc), U256::from(2))
+            .outcome_asset_structure(OutcomeAssetStructure::Any)
+            .fill_structure(FillStructure::PercentageFilled)
+            .valid_for(Duration::from_secs(600))
+            .nonce(U256::from(7));
+        let intent = builder.build_at(1_000).unwrap();
+        assert_eq!(intent.valid_before, U256::from(1_600));
+        assert_eq!(intent.outcome.m_tokens.len(), 2);
+
+        let expired = builder.valid_before(U256::from(900));
+        assert!(matches!(
+            expired.build_at(1_000),
+            Err(IntentBuilderError::Invalid(
+                IntentValidationError::IntentExpired
+            ))
+        ));
+        assert!(matches!(
+            expired.receive(Address::ZERO, U256::from(1)).build_at(0),
+            Err(IntentBuilderError::Invalid(
+                IntentValidationError::ZeroOutcomeToken { index: 2, .. }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cross_chain_intent_signing() {
+        let signer = alloy::signers::local::PrivateKeySigner::random();
+        let builder = CrossChainIntentBuilder::new(signer.address())
+            .sell(Address::repeat_byte(0xaa), U256::from(100))
+            .destination_chain(42161)
+            .native_outcome(U256::from(1))
+            .valid_for(Duration::from_secs(60))
+            .nonce(U256::from(1));
+        let intent = builder.build_at(1_000).unwrap();
+        assert_eq!(intent.validBefore, U256::from(1_060));
+
+        let deployment = test_deployment();
+        let signature = intent.sign(&signer, &deployment).await.unwrap();
+        let signature = alloy::primitives::Signature::try_from(signature.as_ref()).unwrap();
+        let recovered = signature
+            .recover_address_from_prehash(&alloy::sol_types::SolStruct::eip712_signing_hash(
+                &intent,
+                &deployment.intent_domain(),
+            ))
+            .unwrap();
+        assert_eq!(recovered, signer.address());
+
+        assert!(matches!(
+            builder.clone().native_outcome(U256::ZERO).build_at(1_000),
+            Err(IntentBuilderError::Invalid(
+                IntentValidationError::MissingField {
+                    field: "outcome",
+                    ..
+                }
+            ))
+        ));
+
+        let token = Token {
+            spoke_address: Address::repeat_byte(0xbb),
+            mtoken_address: Address::repeat_byte(0xaa),
+            spoke_chain_id: u64::from(u32::MAX) + 1,
+            symbol: "USDC".into(),
+            decimals: 6,
+        };
+        assert!(matches!(
+            builder.receive_token(&token, U256::from(1)),
+            Err(IntentBuilderError::Token(
+                TokenRegistryError::ChainIdOutOfRange { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+//! Offline checks mirroring the on-chain `IntentValidator` publish rules.
+//!
+//! Front-ends can run these before `propose_intent` to report precise errors instead of an
+//! on-chain revert. Every error variant converts into the matching
+//! `IntentValidator__PublishError__*` variant of `All::AllErrors`.
+
+use alloy::primitives::{B256, U256};
+use thiserror::Error;
+
+use super::intents::Intent;
+use super::sol_types::{All, CrossChainIntent};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IntentValidationError {
+    #[error("valid_after is larger than valid_before")]
+    ValidAfterLargerThanValidBefore,
+    #[error("intent nonce must be larger than the author's current nonce")]
+    InvalidIntentNonce,
+    #[error("intent expired")]
+    IntentExpired,
+    #[error("intent {intent_id} is missing {field}")]
+    MissingField {
+        intent_id: B256,
+        field: &'static str,
+    },
+    #[error("outcome amounts are too large to fill the intent pro rata")]
+    MAmountsMustYieldManageableOutcomes,
+    /// Only reported by the IntentBook, which knows the published intents.
+    #[error("intent {0} already exists")]
+    IntentAlreadyExists(B256),
+    #[error("intent {0} has a different number of outcome mTokens and amounts")]
+    OutcomeMTokenAndMAmountLengthMismatch(B256),
+    /// Only reported on-chain.
+    #[error("intent {0} has an unsupported type")]
+    UnsupportedIntentType(B256),
+    #[error("intent {intent_id}: outcome mToken {index} is the zero address")]
+    ZeroOutcomeToken { intent_id: B256, index: usize },
+    #[error("intent {intent_id}: outcome amount {index} is zero")]
+    ZeroOutcomeAmount { intent_id: B256, index: usize },
+}
+
+macro_rules! revert {
+    ($name:ident { $($body:tt)* }) => {
+        All::AllErrors::$name(All::$name { $($body)* })
+    };
+}
+
+impl From<IntentValidationError> for All::AllErrors {
+    fn from(err: IntentValidationError) -> Self {
+        use IntentValidationError as E;
+        match err {
+            E::ValidAfterLargerThanValidBefore => {
+                revert!(IntentValidator__PublishError__ValidAfterLargerThanValidBefore {})
+            }
+            E::InvalidIntentNonce => revert!(IntentValidator__PublishError__InvalidIntentNonce {}),
+            E::IntentExpired => revert!(IntentValidator__PublishError__IntentExpired {}),
+            E::MissingField { intent_id, .. } => {
+                revert!(IntentValidator__PublishError__MissingField {
+                    intentId: intent_id
+                })
+            }
+            E::MAmountsMustYieldManageableOutcomes => {
+                revert!(IntentValidator__PublishError__MAmountsMustYieldManageableOutcomes {})
+            }
+            E::IntentAlreadyExists(intent_id) => {
+                revert!(IntentValidator__PublishError__IntentAlreadyExists {
+                    intentId: intent_id
+                })
+            }
+            E::OutcomeMTokenAndMAmountLengthMismatch(intent_id) => {
+                revert!(
+                    IntentValidator__PublishError__OutcomeMTokenAndMAmountLengthMismatch {
+                        intentId: intent_id
+                    }
+                )
+            }
+            E::UnsupportedIntentType(intent_id) => {
+                revert!(IntentValidator__PublishError__UnsupportedIntentType {
+                    intentId: intent_id
+                })
+            }
+            E::ZeroOutcomeToken { intent_id, index } => {
+                revert!(IntentValidator__PublishError__ZeroOutcomeToken {
+                    intentId: intent_id,
+                    index: U256::from(index),
+                })
+            }
+            E::ZeroOutcomeAmount { intent_id, index } => {
+                revert!(IntentValidator__PublishError__ZeroOutcomeAmount {
+                    intentId: intent_id,
+                    index: U256::from(index),
+                })
+            }
+        }
+    }
+}
+
+impl Intent {
+    /// Checks the intent the same way the `IntentValidator` does when it is published at `now`
+    /// (unix seconds) by an author whose current nonce is `nonce`.
+    pub fn validate(&self, now: u64, nonce: U256) -> Result<(), IntentValidationError> {
+        use IntentValidationError as E;
+
+        let intent_id = self.intent_id();
+        let missing = |field| E::MissingField { intent_id, field };
+        if self.author.is_zero() {
+            return Err(missing("author"));
+        }
+        if self.src_m_token.is_zero() {
+            return Err(missing("src_m_token"));
+        }
+        if self.src_amount.is_zero() {
+            return Err(missing("src_amount"));
+        }
+        if self.outcome.m_tokens.is_empty() {
+            return Err(missing("outcome"));
+        }
+        if self.outcome.m_tokens.len() != self.outcome.m_amounts.len() {
+            return Err(E::OutcomeMTokenAndMAmountLengthMismatch(intent_id));
+        }
+        for (index, (m_token, m_amount)) in self
+            .outcome
+            .m_tokens
+            .iter()
+            .zip(&self.outcome.m_amounts)
+            .enumerate()
+        {
+            if m_token.is_zero() {
+                return Err(E::ZeroOutcomeToken { intent_id, index });
+            }
+            if m_amount.is_zero() {
+                return Err(E::ZeroOutcomeAmount { intent_id, index });
+            }
+            // Partial fills scale outcome amounts by the spent share of the source amount.
+            if m_amount.checked_mul(self.src_amount).is_none() {
+                return Err(E::MAmountsMustYieldManageableOutcomes);
+            }
+        }
+        if self.valid_after > self.valid_before {
+            return Err(E::ValidAfterLargerThanValidBefore);
+        }
+        if self.valid_before <= U256::from(now) {
+            return Err(E::IntentExpired);
+        }
+        if self.nonce <= nonce {
+            return Err(E::InvalidIntentNonce);
+        }
+        Ok(())
+    }
+}
+
+impl CrossChainIntent {
+    /// The subset of [`Intent::validate`] that applies to cross-chain intents.
+    pub fn validate(&self, now: u64, nonce: U256) -> Result<(), IntentValidationError> {
+        use IntentValidationError as E;
+
+        let intent_id = self.intent_id();
+        let missing = |field| E::MissingField { intent_id, field };
+        if self.author.is_zero() {
+            return Err(missing("author"));
+        }
+        if self.srcMToken.is_zero() {
+            return Err(missing("src_m_token"));
+        }
+        if self.srcAmount.is_zero() {
+            return Err(missing("src_amount"));
+        }
+        if self.destinationChainId == 0 {
+            return Err(missing("destination_chain_id"));
+        }
+        if self.outcomeToken.is_zero() && self.nativeOutcome.is_zero() {
+            return Err(missing("outcome"));
+        }
+        if !self.outcomeToken.is_zero() && self.outcomeAmount.is_zero() {
+            return Err(E::ZeroOutcomeAmount {
+                intent_id,
+                index: 0,
+            });
+        }
+        if self.validBefore <= U256::from(now) {
+            return Err(E::IntentExpired);
+        }
+        if self.nonce <= nonce {
+            return Err(E::InvalidIntentNonce);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Address;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_maps_to_reverts() {
+        let mut intent = Intent::simple_swap(
+            Address::repeat_byte(1),
+            U256::from(2_000),
+            U256::ZERO,
+            Some(U256::from(5)),
+            Address::repeat_byte(0xaa),
+            U256::from(100),
+            Address::repeat_byte(0xbb),
+            U256::from(1),
+        );
+        assert_eq!(intent.validate(1_000, U256::from(4)), Ok(()));
+        assert_eq!(
+            intent.validate(1_000, U256::from(5)),
+            Err(IntentValidationError::InvalidIntentNonce)
+        );
+        assert_eq!(
+            intent.validate(2_000, U256::ZERO),
+            Err(IntentValidationError::IntentExpired)
+        );
+
+        intent.outcome.m_amounts[0] = U256::ZERO;
+        let err = intent.validate(1_000, U256::ZERO).unwrap_err();
+        assert!(matches!(
+            All::AllErrors::from(err),
+            All::AllErrors::IntentValidator__PublishError__ZeroOutcomeAmount(e)
+                if e.intentId == intent.intent_id() && e.index.is_zero()
+        ));
+    }
+}
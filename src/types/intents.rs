@@ -1,13 +1,15 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use alloy::primitives::{Address, Bytes, U256};
-use alloy::sol_types::{SolStruct, eip712_domain};
+use alloy::primitives::{Address, Bytes, U256, U512};
+use alloy::sol_types::SolStruct;
 use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 use serde_with::{TryFromInto, serde_as};
 
 use super::common::*;
 use super::conversion::*;
+use super::deployment::ArcadiaDeployment;
+use super::token::Token;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[serde(rename = "OutcomeAssetStructure")]
@@ -161,17 +163,42 @@ impl Intent {
         }
     }
 
-    pub async fn sign<S>(&self, signer: &S, intent_book: Address, chain_id: u64) -> SignedIntent
+    /// [`Intent::simple_swap`] between the mTokens of two registry tokens. Amounts are in mToken
+    /// units (18 decimals).
+    #[allow(clippy::too_many_arguments)]
+    pub fn simple_swap_tokens(
+        author: Address,
+        valid_before: U256,
+        valid_after: U256,
+        nonce: Option<U256>,
+        src_token: &Token,
+        src_amount: U256,
+        output_token: &Token,
+        output_amount: U256,
+    ) -> Self {
+        Self::simple_swap(
+            author,
+            valid_before,
+            valid_after,
+            nonce,
+            src_token.mtoken_address,
+            src_amount,
+            output_token.mtoken_address,
+            output_amount,
+        )
+    }
+
+    /// The hash [`Intent::sign`] signs, under [`ArcadiaDeployment::intent_domain`].
+    pub fn signing_hash(&self, deployment: &ArcadiaDeployment) -> B256 {
+        self.convert_to_sol_type()
+            .eip712_signing_hash(&deployment.intent_domain())
+    }
+
+    pub async fn sign<S>(&self, signer: &S, deployment: &ArcadiaDeployment) -> SignedIntent
     where
         S: alloy::signers::Signer,
     {
-        let domain = eip712_domain!(
-            name: "KhalaniIntent",
-            version: "1.0.0",
-            chain_id: chain_id,
-            verifying_contract: intent_book,
-        );
-        let signing_hash = self.convert_to_sol_type().eip712_signing_hash(&domain);
+        let signing_hash = self.signing_hash(deployment);
         let signature = signer
             .sign_hash(&signing_hash)
             .await
@@ -185,6 +212,33 @@ impl Intent {
         }
     }
 
+    /// The remaining intent after `spent` of the source amount has been filled: the next nonce,
+    /// the unspent source amount and outcome amounts scaled down pro rata (rounded up).
+    ///
+    /// `None` if `spent` exceeds the source amount, the source amount is zero or the nonce is
+    /// already at its maximum.
+    pub fn remainder_after(&self, spent: U256) -> Option<Intent> {
+        if self.src_amount.is_zero() {
+            return None;
+        }
+        let remaining = self.src_amount.checked_sub(spent)?;
+        let mut child = self.clone();
+        child.nonce = self.nonce.checked_add(U256::from(1))?;
+        child.src_amount = remaining;
+        child.outcome.m_amounts = self
+            .outcome
+            .m_amounts
+            .iter()
+            .map(|&amount| {
+                // At most `amount`, so it always fits back into a U256.
+                let scaled = (U512::from(amount) * U512::from(remaining))
+                    .div_ceil(U512::from(self.src_amount));
+                U256::from(scaled)
+            })
+            .collect();
+        Some(child)
+    }
+
     pub fn intent_hash(&self) -> B256 {
         self.convert_to_sol_type().eip712_hash_struct()
     }
@@ -215,40 +269,195 @@ pub struct IntentHistory {
     pub error_type: Option<IntentErrorType>,
 }
 
+/// Where an intent is in its lifecycle, derived from its [`IntentHistory`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum IntentPhase {
+    /// Nothing recorded yet.
+    Pending,
+    Published,
+    Solved,
+    Redeemed,
+    Withdrawn,
+    WithdrawnToSpoke,
+    Cancelled,
+    Failed(IntentErrorType),
+}
+
+impl IntentPhase {
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            IntentPhase::WithdrawnToSpoke | IntentPhase::Cancelled | IntentPhase::Failed(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum IntentHistoryError {
+    #[error("{event} is not allowed in phase {phase:?}")]
+    InvalidTransition {
+        event: &'static str,
+        phase: IntentPhase,
+    },
+    #[error("timestamp {timestamp} is before the previous milestone at {previous}")]
+    TimestampBeforePrevious { timestamp: u64, previous: u64 },
+}
+
+/// Seconds between consecutive milestones, where both are recorded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MilestoneDurations {
+    pub publish_to_solve: Option<Duration>,
+    pub solve_to_redeem: Option<Duration>,
+    pub redeem_to_withdraw: Option<Duration>,
+    pub withdraw_to_spoke: Option<Duration>,
+    pub publish_to_cancel: Option<Duration>,
+    /// From publish to the latest milestone.
+    pub total: Option<Duration>,
+}
+
 impl IntentHistory {
+    /// Records `event` with the current time, in any order. Use
+    /// [`IntentHistory::apply_event_at`] to reject invalid transitions.
     pub fn update_field(&mut self, event: IntentEvent) -> Result<()> {
+        self.record(event, current_timestamp_sec());
+        Ok(())
+    }
+
+    /// Records `event` as having happened at `timestamp` (unix seconds), e.g. when backfilling
+    /// from chain data. Events must follow the lifecycle publish, solve, redeem, withdraw,
+    /// withdraw to spoke, or publish then cancel. Errors are recorded alongside the phase they
+    /// occurred in; see [`IntentHistory::phase`].
+    pub fn apply_event_at(
+        &mut self,
+        event: IntentEvent,
+        timestamp: u64,
+    ) -> Result<(), IntentHistoryError> {
+        use IntentPhase as P;
+
+        let phase = self.phase();
+        let allowed = match &event {
+            IntentEvent::Publish(_) => phase == P::Pending,
+            IntentEvent::Solve(..) | IntentEvent::Cancel(_) => phase == P::Published,
+            IntentEvent::Redeem(_) => phase == P::Solved,
+            IntentEvent::Withdraw(_) => phase == P::Redeemed,
+            IntentEvent::WithdrawReachSpoke() => phase == P::Withdrawn,
+            IntentEvent::Error(error_type, _) => match error_type {
+                IntentErrorType::Publish => phase == P::Pending,
+                IntentErrorType::Cancel | IntentErrorType::Solve => phase == P::Published,
+                IntentErrorType::Withdraw => phase == P::Redeemed,
+                IntentErrorType::WithdrawToSpoke => phase == P::Withdrawn,
+            },
+        };
+        if !allowed {
+            return Err(IntentHistoryError::InvalidTransition {
+                event: event.name(),
+                phase,
+            });
+        }
+        if let Some(previous) = self.latest_timestamp()
+            && timestamp < previous
+        {
+            return Err(IntentHistoryError::TimestampBeforePrevious {
+                timestamp,
+                previous,
+            });
+        }
+        self.record(event, timestamp);
+        Ok(())
+    }
+
+    fn record(&mut self, event: IntentEvent, timestamp: u64) {
         match event {
             IntentEvent::Publish(tx_hash) => {
-                self.publish_timestamp = Some(current_timestamp_sec());
+                self.publish_timestamp = Some(timestamp);
                 self.publish_tx_hash = Some(tx_hash);
             }
             IntentEvent::Solve(tx_hash, remaining_intent_id) => {
-                self.solve_timestamp = Some(current_timestamp_sec());
+                self.solve_timestamp = Some(timestamp);
                 self.solve_tx_hash = Some(tx_hash);
                 self.remaining_intent_id = remaining_intent_id;
             }
             IntentEvent::Redeem(tx_hash) => {
-                self.redeem_timestamp = Some(current_timestamp_sec());
+                self.redeem_timestamp = Some(timestamp);
                 self.redeem_tx_hash = Some(tx_hash);
             }
             IntentEvent::Withdraw(tx_hash) => {
-                self.withdraw_timestamp = Some(current_timestamp_sec());
+                self.withdraw_timestamp = Some(timestamp);
                 self.withdraw_tx_hash = Some(tx_hash);
             }
             IntentEvent::WithdrawReachSpoke() => {
-                self.withdraw_to_spoke_timestamp = Some(current_timestamp_sec());
+                self.withdraw_to_spoke_timestamp = Some(timestamp);
             }
             IntentEvent::Cancel(tx_hash) => {
-                self.cancel_timestamp = Some(current_timestamp_sec());
+                self.cancel_timestamp = Some(timestamp);
                 self.cancel_tx_hash = Some(tx_hash);
             }
             IntentEvent::Error(error_type, tx_hash) => {
-                self.error_timestamp = Some(current_timestamp_sec());
+                self.error_timestamp = Some(timestamp);
                 self.error_tx_hash = Some(tx_hash);
                 self.error_type = Some(error_type);
             }
         };
-        Ok(())
+    }
+
+    /// The phase reached by the recorded milestones. A failed publish or withdrawal to the spoke
+    /// chain ends the lifecycle as [`IntentPhase::Failed`]. Other errors (a failed cancel, solve
+    /// or withdrawal) can be retried, so they leave the phase unchanged.
+    pub fn phase(&self) -> IntentPhase {
+        let phase = self.milestone_phase();
+        match (&self.error_type, &phase) {
+            (Some(error_type @ IntentErrorType::Publish), IntentPhase::Pending)
+            | (Some(error_type @ IntentErrorType::WithdrawToSpoke), IntentPhase::Withdrawn) => {
+                IntentPhase::Failed(error_type.clone())
+            }
+            _ => phase,
+        }
+    }
+
+    fn milestone_phase(&self) -> IntentPhase {
+        if self.cancel_timestamp.is_some() {
+            IntentPhase::Cancelled
+        } else if self.withdraw_to_spoke_timestamp.is_some() {
+            IntentPhase::WithdrawnToSpoke
+        } else if self.withdraw_timestamp.is_some() {
+            IntentPhase::Withdrawn
+        } else if self.redeem_timestamp.is_some() {
+            IntentPhase::Redeemed
+        } else if self.solve_timestamp.is_some() {
+            IntentPhase::Solved
+        } else if self.publish_timestamp.is_some() {
+            IntentPhase::Published
+        } else {
+            IntentPhase::Pending
+        }
+    }
+
+    pub fn durations(&self) -> MilestoneDurations {
+        let between =
+            |from: Option<u64>, to: Option<u64>| Some(Duration::from_secs(to?.checked_sub(from?)?));
+        MilestoneDurations {
+            publish_to_solve: between(self.publish_timestamp, self.solve_timestamp),
+            solve_to_redeem: between(self.solve_timestamp, self.redeem_timestamp),
+            redeem_to_withdraw: between(self.redeem_timestamp, self.withdraw_timestamp),
+            withdraw_to_spoke: between(self.withdraw_timestamp, self.withdraw_to_spoke_timestamp),
+            publish_to_cancel: between(self.publish_timestamp, self.cancel_timestamp),
+            total: between(self.publish_timestamp, self.latest_timestamp()),
+        }
+    }
+
+    fn latest_timestamp(&self) -> Option<u64> {
+        [
+            self.publish_timestamp,
+            self.solve_timestamp,
+            self.redeem_timestamp,
+            self.withdraw_timestamp,
+            self.withdraw_to_spoke_timestamp,
+            self.cancel_timestamp,
+            self.error_timestamp,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
     }
 }
 
@@ -263,6 +472,20 @@ pub enum IntentEvent {
     Error(IntentErrorType, B256),
 }
 
+impl IntentEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            IntentEvent::Publish(_) => "Publish",
+            IntentEvent::Solve(..) => "Solve",
+            IntentEvent::Redeem(_) => "Redeem",
+            IntentEvent::Withdraw(_) => "Withdraw",
+            IntentEvent::Cancel(_) => "Cancel",
+            IntentEvent::WithdrawReachSpoke() => "WithdrawReachSpoke",
+            IntentEvent::Error(..) => "Error",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[repr(u8)]
 pub enum IntentErrorType {
@@ -299,3 +522,102 @@ pub fn current_timestamp_ms() -> u128 {
         .unwrap()
         .as_millis()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_deployment;
+
+    #[test]
+    fn test_history_transitions() {
+        let mut history = IntentHistory::default();
+        assert_eq!(
+            history.apply_event_at(IntentEvent::Redeem(B256::ZERO), 10),
+            Err(IntentHistoryError::InvalidTransition {
+                event: "Redeem",
+                phase: IntentPhase::Pending,
+            })
+        );
+        history
+            .apply_event_at(IntentEvent::Publish(B256::ZERO), 10)
+            .unwrap();
+        history
+            .apply_event_at(IntentEvent::Solve(B256::ZERO, None), 25)
+            .unwrap();
+        assert_eq!(
+            history.apply_event_at(IntentEvent::Redeem(B256::ZERO), 20),
+            Err(IntentHistoryError::TimestampBeforePrevious {
+                timestamp: 20,
+                previous: 25,
+            })
+        );
+        history
+            .apply_event_at(IntentEvent::Redeem(B256::ZERO), 30)
+            .unwrap();
+        assert_eq!(history.phase(), IntentPhase::Redeemed);
+
+        let durations = history.durations();
+        assert_eq!(durations.publish_to_solve, Some(Duration::from_secs(15)));
+        assert_eq!(durations.redeem_to_withdraw, None);
+        assert_eq!(durations.total, Some(Duration::from_secs(20)));
+
+        // A failed withdrawal can be retried.
+        history
+            .apply_event_at(
+                IntentEvent::Error(IntentErrorType::Withdraw, B256::ZERO),
+                35,
+            )
+            .unwrap();
+        assert_eq!(history.phase(), IntentPhase::Redeemed);
+        assert_eq!(history.error_type, Some(IntentErrorType::Withdraw));
+        history
+            .apply_event_at(IntentEvent::Withdraw(B256::ZERO), 40)
+            .unwrap();
+        history
+            .apply_event_at(
+                IntentEvent::Error(IntentErrorType::WithdrawToSpoke, B256::ZERO),
+                45,
+            )
+            .unwrap();
+        assert_eq!(
+            history.phase(),
+            IntentPhase::Failed(IntentErrorType::WithdrawToSpoke)
+        );
+        assert!(history.phase().is_terminal());
+
+        // `update_field` records events in any order.
+        let mut history = IntentHistory::default();
+        history
+            .update_field(IntentEvent::Redeem(B256::ZERO))
+            .unwrap();
+        assert_eq!(history.phase(), IntentPhase::Redeemed);
+    }
+
+    #[tokio::test]
+    async fn test_signing_hash_uses_chain_id() {
+        let signer = alloy::signers::local::PrivateKeySigner::random();
+        let deployment = test_deployment();
+        let intent = Intent::simple_swap(
+            signer.address(),
+            U256::from(2_000),
+            U256::ZERO,
+            None,
+            Address::repeat_byte(0xaa),
+            U256::from(100),
+            Address::repeat_byte(0xbb),
+            U256::from(1),
+        );
+        let signed = intent.sign(&signer, &deployment).await;
+        let signature = alloy::primitives::Signature::from_raw(&signed.signature).unwrap();
+        let hash = intent.signing_hash(&deployment);
+        assert_eq!(
+            signature.recover_address_from_prehash(&hash).unwrap(),
+            signer.address()
+        );
+        // The legacy hash keeps its domain without a chain id.
+        assert_ne!(
+            crate::types::sol_types::eip712_intent_hash(&intent, deployment.intent_book),
+            hash
+        );
+    }
+}
@@ -1,10 +1,15 @@
 pub mod common;
 pub mod conversion;
+pub mod deployment;
+pub mod intent_builder;
+pub mod intent_validator;
 pub mod intents;
 pub mod receipt;
 pub mod refinement;
 pub mod rpc_payloads;
 pub mod sol_types;
 pub mod solution;
+pub mod solution_validator;
 pub mod token;
+pub mod token_registry;
 pub mod ws;
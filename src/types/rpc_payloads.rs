@@ -1,3 +1,4 @@
+use super::deployment::ArcadiaDeployment;
 use super::sol_types::FastWithdrawalPermit;
 use alloy::dyn_abi::TypedData;
 use alloy::primitives::{Address, B256, Bytes, Signature, U256};
@@ -6,15 +7,26 @@ use alloy::sol_types::{Eip712Domain, SolStruct};
 use anyhow::{Context, ensure};
 use serde::{Deserialize, Serialize};
 
+/// A Medusa payload, signed under the domain its deployment assigns to its kind.
+pub trait SignablePayload: SolStruct {
+    fn domain(deployment: &ArcadiaDeployment) -> Eip712Domain;
+}
+
 macro_rules! impl_signable {
-    ($type:ty) => {
+    ($type:ty, $domain:ident) => {
+        impl SignablePayload for $type {
+            fn domain(deployment: &ArcadiaDeployment) -> Eip712Domain {
+                deployment.$domain()
+            }
+        }
+
         impl $type {
             pub async fn sign(
                 self,
                 signer: &(impl alloy::signers::Signer + Send + Sync),
-                domain: &Eip712Domain,
+                deployment: &ArcadiaDeployment,
             ) -> Result<SignedPayload<Self>, alloy::signers::Error> {
-                let hash = self.eip712_signing_hash(domain);
+                let hash = self.eip712_signing_hash(&Self::domain(deployment));
                 let signature = signer.sign_hash(&hash).await?;
                 Ok(SignedPayload {
                     payload: self,
@@ -68,11 +80,11 @@ sol! {
     }
 }
 
-impl_signable!(CancelIntent);
-impl_signable!(AddSolver);
-impl_signable!(Withdraw);
-impl_signable!(VaultDeposit);
-impl_signable!(VaultWithdraw);
+impl_signable!(CancelIntent, payload_domain);
+impl_signable!(AddSolver, payload_domain);
+impl_signable!(Withdraw, payload_domain);
+impl_signable!(VaultDeposit, teller_domain);
+impl_signable!(VaultWithdraw, teller_domain);
 
 pub type SignedCancelIntent = SignedPayload<CancelIntent>;
 pub type SignedAddSolver = SignedPayload<AddSolver>;
@@ -94,10 +106,13 @@ pub struct SignedPayload<T> {
 
 impl<T> SignedPayload<T>
 where
-    T: SolStruct,
+    T: SignablePayload,
 {
-    pub fn recover_signer_address(&self, domain: &Eip712Domain) -> anyhow::Result<Address> {
-        let hash = self.payload.eip712_signing_hash(&domain);
+    pub fn recover_signer_address(
+        &self,
+        deployment: &ArcadiaDeployment,
+    ) -> anyhow::Result<Address> {
+        let hash = self.payload.eip712_signing_hash(&T::domain(deployment));
         let addr = Signature::from_raw(&self.signature)?.recover_address_from_prehash(&hash)?;
         Ok(addr)
     }
@@ -131,25 +146,29 @@ pub fn validate_and_extract_fast_withdrawal_permit_with_witness_typed_data(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::test_deployment;
     use alloy::primitives::U256;
-    use alloy::sol_types::eip712_domain;
 
     #[tokio::test]
     async fn test_signing_and_recovery() {
         let signer = alloy::signers::local::PrivateKeySigner::random();
-        let domain = eip712_domain!(
-            name: "Arcadia",
-            version: "1",
-            chain_id: 1,
-            verifying_contract: "0x1234567890123456789012345678901234567890".parse().unwrap(),
-        );
+        let deployment = test_deployment();
 
         let payload = CancelIntent {
             intentId: B256::random(),
             nonce: U256::from(1),
         };
-        let signed_payload = payload.sign(&signer, &domain).await.unwrap();
-        let recovered_address = signed_payload.recover_signer_address(&domain).unwrap();
+        let signed_payload = payload.sign(&signer, &deployment).await.unwrap();
+        let recovered_address = signed_payload.recover_signer_address(&deployment).unwrap();
         assert_eq!(recovered_address, signer.address());
+
+        let other_chain = ArcadiaDeployment {
+            chain_id: 2,
+            ..deployment
+        };
+        assert_ne!(
+            signed_payload.recover_signer_address(&other_chain).unwrap(),
+            signer.address()
+        );
     }
 }
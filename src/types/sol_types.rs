@@ -4,6 +4,7 @@ use alloy::sol_types::{Eip712Domain, SolStruct, eip712_domain};
 use serde::{Deserialize, Serialize};
 
 use super::conversion::{RpcToSol, SolidityType};
+use super::deployment::ArcadiaDeployment;
 use super::intents::Intent as RpcIntent;
 use super::{intents, receipt, solution};
 
@@ -440,6 +441,9 @@ impl From<intents::Intent> for Intent {
     }
 }
 
+#[deprecated(
+    note = "has no chain id, unlike the domain intents are signed under; use `intent_domain`"
+)]
 pub fn eip712_domain(verifying_contract: Address) -> Eip712Domain {
     eip712_domain! {
         name: "KhalaniIntent".to_string(),
@@ -448,11 +452,47 @@ pub fn eip712_domain(verifying_contract: Address) -> Eip712Domain {
     }
 }
 
+/// Hashes `intent` under [`eip712_domain`], which has no chain id. Signatures from
+/// [`intents::Intent::sign`] are over [`intents::Intent::signing_hash`] instead.
+#[allow(deprecated)]
 pub fn eip712_intent_hash(intent: &RpcIntent, intent_book: Address) -> B256 {
     let domain = eip712_domain(intent_book);
     intent.convert_to_sol_type().eip712_signing_hash(&domain)
 }
 
+/// The IntentBook's domain: `KhalaniIntent` version `1.0.0` with the Arcadia chain id, the
+/// domain [`intents::Intent::sign`] has always signed intents under.
+pub fn intent_domain(chain_id: u64, intent_book: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: "KhalaniIntent",
+        version: "1.0.0",
+        chain_id: chain_id,
+        verifying_contract: intent_book,
+    }
+}
+
+/// The MTokenManager's domain for fast withdrawal permits: `FastWithdrawalPermit` version `1`
+/// with the Arcadia chain id, the domain [`FastWithdrawalPermit::sign`] has always used.
+pub fn fast_withdrawal_domain(chain_id: u64, mtoken_manager: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: "FastWithdrawalPermit",
+        version: "1",
+        chain_id: chain_id,
+        verifying_contract: mtoken_manager,
+    }
+}
+
+/// The Teller's domain for vault deposits and withdrawals: the [`intent_domain`] fields with
+/// the Teller as verifying contract.
+pub fn teller_domain(chain_id: u64, teller: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: "KhalaniIntent",
+        version: "1.0.0",
+        chain_id: chain_id,
+        verifying_contract: teller,
+    }
+}
+
 impl SolidityType for OutcomeAssetStructure {}
 impl SolidityType for FillStructure {}
 impl SolidityType for Outcome {}
@@ -471,16 +511,26 @@ impl FastWithdrawalPermit {
     pub async fn sign(
         self,
         signer: &(impl alloy::signers::Signer + Send + Sync),
-        chain_id: u64,
-        mtoken_manager: Address,
+        deployment: &ArcadiaDeployment,
+    ) -> Result<alloy::primitives::Bytes, alloy::signers::Error> {
+        let hash = self.eip712_signing_hash(&deployment.fast_withdrawal_domain());
+        let signature = signer.sign_hash(&hash).await?;
+        Ok(signature.as_bytes().to_vec().into())
+    }
+}
+
+impl CrossChainIntent {
+    pub fn intent_id(&self) -> B256 {
+        self.eip712_hash_struct()
+    }
+
+    /// Signs the intent for the IntentBook, under the same domain as [`intents::Intent::sign`].
+    pub async fn sign(
+        &self,
+        signer: &(impl alloy::signers::Signer + Send + Sync),
+        deployment: &ArcadiaDeployment,
     ) -> Result<alloy::primitives::Bytes, alloy::signers::Error> {
-        let domain = eip712_domain! {
-            name: "FastWithdrawalPermit".to_string(),
-            version: "1".to_string(),
-            chain_id: chain_id,
-            verifying_contract: mtoken_manager,
-        };
-        let hash = self.eip712_signing_hash(&domain);
+        let hash = self.eip712_signing_hash(&deployment.intent_domain());
         let signature = signer.sign_hash(&hash).await?;
         Ok(signature.as_bytes().to_vec().into())
     }
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use alloy::primitives::{Address, Bytes, Signature, U256, keccak256};
 use alloy::signers::Signer;
 use alloy::sol_types::SolValue;
@@ -7,8 +9,9 @@ use super::common::*;
 use super::conversion::{RpcToSol, RpcType};
 use super::intents::Intent;
 use super::receipt::Receipt;
+use super::solution_validator::SolutionValidationError;
 
-#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone, Copy)]
 pub enum OutType {
     Intent,
     Receipt,
@@ -57,6 +60,10 @@ pub struct Solution {
 }
 
 impl Solution {
+    pub fn builder() -> SolutionBuilder {
+        SolutionBuilder::default()
+    }
+
     pub async fn sign<S>(&self, signer: &S) -> SignedSolution
     where
         S: Signer,
@@ -75,6 +82,243 @@ impl Solution {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SolutionBuilderError {
+    #[error("handle belongs to a different builder")]
+    ForeignHandle,
+    /// The handle was issued by a clone of this builder after they diverged.
+    #[error("handle refers to an entry this builder does not have")]
+    UnknownHandle,
+    #[error("cannot keep a remainder of input {input} after spending {spent}")]
+    InvalidRemainder { input: usize, spent: U256 },
+}
+
+/// Distinguishes builders so handles from one are refused by another.
+static NEXT_BUILDER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Refers to an input intent added to a [`SolutionBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputHandle {
+    builder: u64,
+    idx: usize,
+}
+
+/// Refers to an output added to a [`SolutionBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OutputHandle {
+    builder: u64,
+    out_type: OutType,
+    idx: usize,
+}
+
+impl OutputHandle {
+    pub fn out_type(&self) -> OutType {
+        self.out_type
+    }
+
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+}
+
+impl From<OutputHandle> for OutputIdx {
+    fn from(handle: OutputHandle) -> Self {
+        OutputIdx {
+            out_type: handle.out_type,
+            out_idx: handle.idx as u64,
+        }
+    }
+}
+
+/// Assembles a [`Solution`] from handles instead of raw indices.
+///
+/// ```ignore
+/// let mut builder = Solution::builder();
+/// let a = builder.add_input(sells_usdc_for_eth);
+/// let b = builder.add_input(sells_eth_for_usdc);
+/// builder.deliver(b, a, eth_amount)?;
+/// builder.deliver(a, b, usdc_amount)?;
+/// let solution = builder.build()?;
+/// ```
+///
+/// Methods taking handles fail with [`SolutionBuilderError::ForeignHandle`] when given a handle
+/// issued by another builder. Clones share handles, so a handle a clone issued later fails
+/// with [`SolutionBuilderError::UnknownHandle`] instead.
+#[derive(Debug, Clone)]
+pub struct SolutionBuilder {
+    id: u64,
+    inputs: Vec<Intent>,
+    intent_outputs: Vec<Intent>,
+    receipt_outputs: Vec<Receipt>,
+    spend_graph: Vec<MoveRecord>,
+    fill_graph: Vec<FillRecord>,
+}
+
+impl Default for SolutionBuilder {
+    fn default() -> Self {
+        Self {
+            id: NEXT_BUILDER_ID.fetch_add(1, Ordering::Relaxed),
+            inputs: Vec::new(),
+            intent_outputs: Vec::new(),
+            receipt_outputs: Vec::new(),
+            spend_graph: Vec::new(),
+            fill_graph: Vec::new(),
+        }
+    }
+}
+
+impl SolutionBuilder {
+    pub fn add_input(&mut self, intent: Intent) -> InputHandle {
+        self.inputs.push(intent);
+        InputHandle {
+            builder: self.id,
+            idx: self.inputs.len() - 1,
+        }
+    }
+
+    pub fn add_intent_output(&mut self, intent: Intent) -> OutputHandle {
+        self.intent_outputs.push(intent);
+        self.output_handle(OutType::Intent, self.intent_outputs.len() - 1)
+    }
+
+    pub fn add_receipt(&mut self, receipt: Receipt) -> OutputHandle {
+        self.receipt_outputs.push(receipt);
+        self.output_handle(OutType::Receipt, self.receipt_outputs.len() - 1)
+    }
+
+    pub fn input(&self, handle: InputHandle) -> Result<&Intent, SolutionBuilderError> {
+        if handle.builder != self.id {
+            return Err(SolutionBuilderError::ForeignHandle);
+        }
+        self.inputs
+            .get(handle.idx)
+            .ok_or(SolutionBuilderError::UnknownHandle)
+    }
+
+    /// Records a move of `qty` of `from`'s source mToken into `to`.
+    pub fn spend(
+        &mut self,
+        from: InputHandle,
+        to: OutputHandle,
+        qty: U256,
+    ) -> Result<&mut Self, SolutionBuilderError> {
+        self.check_handles(from, to)?;
+        self.spend_graph.push(MoveRecord {
+            src_idx: from.idx as u64,
+            output_idx: to.into(),
+            qty,
+        });
+        Ok(self)
+    }
+
+    /// Records that `output` goes towards filling `input`.
+    pub fn fill(
+        &mut self,
+        input: InputHandle,
+        output: OutputHandle,
+    ) -> Result<&mut Self, SolutionBuilderError> {
+        self.check_handles(input, output)?;
+        let OutputIdx { out_type, out_idx } = output.into();
+        self.fill_graph.push(FillRecord {
+            in_idx: input.idx as u64,
+            out_idx,
+            out_type,
+        });
+        Ok(self)
+    }
+
+    /// Pays `qty` of `from`'s source mToken to the author of `to` through a new receipt that
+    /// fills `to`.
+    pub fn deliver(
+        &mut self,
+        from: InputHandle,
+        to: InputHandle,
+        qty: U256,
+    ) -> Result<OutputHandle, SolutionBuilderError> {
+        let m_token = self.input(from)?.src_m_token;
+        let to_intent = self.input(to)?;
+        let receipt = Receipt {
+            m_token,
+            m_token_amount: qty,
+            owner: to_intent.author,
+            intent_hash: to_intent.intent_id(),
+        };
+        let receipt = self.add_receipt(receipt);
+        self.spend(from, receipt, qty)?.fill(to, receipt)?;
+        Ok(receipt)
+    }
+
+    /// Moves what `input` has not spent after `spent` into a child intent that fills it.
+    ///
+    /// Fails if [`Intent::remainder_after`] does, e.g. when `spent` exceeds the source amount.
+    pub fn remainder(
+        &mut self,
+        input: InputHandle,
+        spent: U256,
+    ) -> Result<OutputHandle, SolutionBuilderError> {
+        let child = self.input(input)?.remainder_after(spent).ok_or(
+            SolutionBuilderError::InvalidRemainder {
+                input: input.idx,
+                spent,
+            },
+        )?;
+        let qty = child.src_amount;
+        let child = self.add_intent_output(child);
+        self.spend(input, child, qty)?.fill(input, child)?;
+        Ok(child)
+    }
+
+    fn output_handle(&self, out_type: OutType, idx: usize) -> OutputHandle {
+        OutputHandle {
+            builder: self.id,
+            out_type,
+            idx,
+        }
+    }
+
+    fn check_handles(
+        &self,
+        input: InputHandle,
+        output: OutputHandle,
+    ) -> Result<(), SolutionBuilderError> {
+        self.input(input)?;
+        if output.builder != self.id {
+            return Err(SolutionBuilderError::ForeignHandle);
+        }
+        let outputs = match output.out_type {
+            OutType::Intent => self.intent_outputs.len(),
+            OutType::Receipt => self.receipt_outputs.len(),
+        };
+        if output.idx >= outputs {
+            return Err(SolutionBuilderError::UnknownHandle);
+        }
+        Ok(())
+    }
+
+    /// Builds the solution, refusing graphs that [`Solution::validate`] rejects.
+    pub fn build(self) -> Result<Solution, SolutionValidationError> {
+        let (solution, inputs) = self.into_parts();
+        solution.validate(&inputs)?;
+        Ok(solution)
+    }
+
+    /// Builds the solution without validating it.
+    pub fn build_unchecked(self) -> Solution {
+        self.into_parts().0
+    }
+
+    fn into_parts(self) -> (Solution, Vec<Intent>) {
+        let solution = Solution {
+            intent_ids: self.inputs.iter().map(Intent::intent_id).collect(),
+            intent_outputs: self.intent_outputs,
+            receipt_outputs: self.receipt_outputs,
+            spend_graph: self.spend_graph,
+            fill_graph: self.fill_graph,
+        };
+        (solution, self.inputs)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SignedSolution {
     pub solution: Solution,
@@ -109,3 +353,131 @@ impl RpcType for OutType {}
 impl RpcType for OutputIdx {}
 impl RpcType for MoveRecord {}
 impl RpcType for FillRecord {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::intents::FillStructure;
+
+    #[test]
+    fn test_builder_rejects_unfilled_input() {
+        let intent = Intent::simple_swap(
+            Address::repeat_byte(1),
+            U256::from(2_000),
+            U256::ZERO,
+            None,
+            Address::repeat_byte(0xaa),
+            U256::from(100),
+            Address::repeat_byte(0xbb),
+            U256::from(1),
+        );
+        let mut builder = Solution::builder();
+        let input = builder.add_input(intent.clone());
+        let receipt = builder.add_receipt(Receipt {
+            m_token: intent.src_m_token,
+            m_token_amount: intent.src_amount,
+            owner: intent.author,
+            intent_hash: intent.intent_id(),
+        });
+        builder.spend(input, receipt, intent.src_amount).unwrap();
+        assert_eq!(
+            builder.clone().build().unwrap_err(),
+            SolutionValidationError::UnfilledInputIntent(0)
+        );
+
+        let solution = builder.build_unchecked();
+        assert_eq!(solution.intent_ids, vec![intent.intent_id()]);
+        assert_eq!(
+            solution.spend_graph[0].output_idx.out_type,
+            OutType::Receipt
+        );
+    }
+
+    fn swap(src_amount: u64, dst_amount: u64) -> Intent {
+        let mut intent = Intent::simple_swap(
+            Address::repeat_byte(1),
+            U256::from(2_000),
+            U256::ZERO,
+            None,
+            Address::repeat_byte(0xaa),
+            U256::from(src_amount),
+            Address::repeat_byte(0xaa),
+            U256::from(dst_amount),
+        );
+        intent.outcome.fill_structure = FillStructure::PercentageFilled;
+        intent
+    }
+
+    #[test]
+    fn test_builder_rejects_over_delivery() {
+        let intent = swap(100, 100);
+        let mut builder = Solution::builder();
+        let input = builder.add_input(intent);
+        builder.deliver(input, input, U256::from(101)).unwrap();
+        assert_eq!(
+            builder.build().unwrap_err(),
+            SolutionValidationError::IntentTokenDoubleSpent(0)
+        );
+    }
+
+    #[test]
+    fn test_builder_remainder() {
+        let intent = swap(100, 10);
+        let mut builder = Solution::builder();
+        let input = builder.add_input(intent.clone());
+        builder.deliver(input, input, U256::from(60)).unwrap();
+        let child = builder.remainder(input, U256::from(60)).unwrap();
+        assert_eq!(child.out_type(), OutType::Intent);
+        let solution = builder.clone().build().unwrap();
+        let remainder = &solution.intent_outputs[child.index()];
+        assert_eq!(remainder.nonce, intent.nonce + U256::from(1));
+        assert_eq!(remainder.src_amount, U256::from(40));
+        assert_eq!(remainder.outcome.m_amounts, vec![U256::from(4)]);
+
+        assert_eq!(
+            builder.remainder(input, U256::from(101)).unwrap_err(),
+            SolutionBuilderError::InvalidRemainder {
+                input: 0,
+                spent: U256::from(101)
+            }
+        );
+        assert!(swap(0, 10).remainder_after(U256::ZERO).is_none());
+        let mut last_nonce = swap(100, 10);
+        last_nonce.nonce = U256::MAX;
+        assert!(last_nonce.remainder_after(U256::from(1)).is_none());
+    }
+
+    #[test]
+    fn test_builder_rejects_foreign_handles() {
+        let mut other = Solution::builder();
+        let foreign = other.add_input(swap(100, 100));
+        let mut builder = Solution::builder();
+        let input = builder.add_input(swap(100, 100));
+        assert_eq!(
+            builder.input(foreign).unwrap_err(),
+            SolutionBuilderError::ForeignHandle
+        );
+        assert_eq!(
+            builder.deliver(input, foreign, U256::from(1)).unwrap_err(),
+            SolutionBuilderError::ForeignHandle
+        );
+
+        let mut clone = builder.clone();
+        let cloned_input = clone.add_input(swap(100, 100));
+        let cloned_output = clone.deliver(input, cloned_input, U256::from(1)).unwrap();
+        assert_eq!(
+            builder.input(cloned_input).unwrap_err(),
+            SolutionBuilderError::UnknownHandle
+        );
+        assert_eq!(
+            builder
+                .spend(input, cloned_output, U256::from(1))
+                .unwrap_err(),
+            SolutionBuilderError::UnknownHandle
+        );
+        assert_eq!(
+            builder.fill(cloned_input, cloned_output).unwrap_err(),
+            SolutionBuilderError::UnknownHandle
+        );
+    }
+}
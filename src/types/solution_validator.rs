@@ -0,0 +1,718 @@
+//! Offline checks mirroring the on-chain `SolutionValidator` and `SolutionLib` rules.
+//!
+//! These let a solver catch malformed solutions before proposing them to Medusa. Every error
+//! variant except [`SolutionValidationError::ArithmeticOverflow`], which the contracts raise as
+//! a Solidity panic, converts into the matching `All::AllErrors` variant.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use alloy::primitives::{Address, B256, U256};
+use thiserror::Error;
+
+use super::intents::{FillStructure, Intent, OutcomeAssetStructure};
+use super::sol_types::All;
+use super::solution::{OutType, Solution};
+
+const WAD: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SolutionValidationError {
+    #[error("solution has no input intents")]
+    EmptySolution,
+    #[error("solution has no receipt outputs")]
+    SolutionMustHaveReceiptOutputs,
+    #[error("duplicate intent id {0}")]
+    DuplicateIntentId(B256),
+    #[error("input intent {0} was not provided")]
+    IntentNotFound(B256),
+    #[error("move record {0}: source index out of bounds")]
+    MoveRecordSrcIdxOutOfBounds(usize),
+    #[error("move record {0}: output index out of bounds")]
+    MoveRecordOutputIdxOutOfBounds(usize),
+    #[error("fill record {0}: input index out of bounds")]
+    FillRecordInIdxOutOfBounds(usize),
+    #[error("fill record {0}: output index out of bounds")]
+    FillRecordOutputIdxOutOfBounds(usize),
+    #[error("move of {input_m_token} into an output of {output_m_token}")]
+    InputOutputTokenTypeMismatch {
+        input_m_token: Address,
+        output_m_token: Address,
+    },
+    #[error("move record {move_idx} spends intent {intent_idx} into an intent it does not fill")]
+    InvalidMoveRecord { intent_idx: usize, move_idx: usize },
+    #[error("intent {0} does not spend its mToken")]
+    IntentMustSpendMToken(usize),
+    #[error("intent {0} spends less than its source amount")]
+    IntentTokenBurnt(usize),
+    #[error("intent {0} spends more than its source amount")]
+    IntentTokenDoubleSpent(usize),
+    #[error("intent output amount does not match the amount moved into it")]
+    IntentAmountMismatch,
+    #[error("receipt amount does not match the amount moved into it")]
+    ReceiptAmountMismatch,
+    #[error("intent output {0} is not part of the fill graph")]
+    OrphanIntentOutput(usize),
+    #[error("receipt output {0} is not funded by any move record")]
+    OrphanReceiptOutput(usize),
+    #[error(
+        "input sum {sum_input} does not match outputs (intents {sum_intent_output}, receipts {sum_receipt_output})"
+    )]
+    InputOutputMismatch {
+        sum_input: U256,
+        sum_intent_output: U256,
+        sum_receipt_output: U256,
+    },
+    #[error("input intent {0} is not filled")]
+    UnfilledInputIntent(usize),
+    #[error("receipt owner does not match the author of the intent it fills")]
+    MismatchBetweenInputAndOutputOwners,
+    #[error("receipt {receipt_idx} mToken is not an outcome of intent {intent_idx}")]
+    ReceiptMTokenNotFoundInIntentOutcome {
+        intent_idx: usize,
+        receipt_idx: usize,
+    },
+    #[error("child intent {0}: author mismatch")]
+    ChildIntentAuthorMismatch(usize),
+    #[error("child intent {0}: mToken mismatch")]
+    ChildIntentMTokenMismatch(usize),
+    #[error("child intent {0}: valid_before mismatch")]
+    ChildIntentValidBeforeMismatch(usize),
+    #[error("child intent {0}: valid_after mismatch")]
+    ChildIntentValidAfterMismatch(usize),
+    #[error("child intent {0}: nonce must be larger than its parent's")]
+    ChildIntentInvalidNonce(usize),
+    #[error("child intent {0}: outcome mismatch")]
+    ChildIntentOutcomeMismatch(usize),
+    #[error("intent outputs {0} and {1} share author and nonce")]
+    DuplicateOutputIntentNonce(usize, usize),
+    #[error("intent {0} is AnySingle but filled with multiple mTokens")]
+    MultipleMTokensFilledForAnySingleIntent(usize),
+    #[error("intent {0} receives no outcome mTokens")]
+    NoMTokensFilledForAnySingleIntent(usize),
+    #[error("intent {0} is Exact and cannot be partially filled")]
+    ExactIntentMustSpendAllMTokens(usize),
+    #[error("intent {0} is Exact and its outcome is not satisfied")]
+    ExactIntentNotSatisfied(usize),
+    #[error("minimum fill amount {minimum_receipt_amount} not met, got {actual_receipt_amount}")]
+    MinimumFillAmountNotSatisfied {
+        minimum_receipt_amount: U256,
+        actual_receipt_amount: U256,
+    },
+    #[error(
+        "percentage fill needs receipts of {expected_receipt_total}, got {actual_receipt_total}"
+    )]
+    InsufficientReceiptsToFillPASIntent {
+        expected_receipt_total: U256,
+        actual_receipt_total: U256,
+    },
+    #[error("intent {0} has an unsupported fill structure")]
+    UnsupportedFillStructure(usize),
+    /// Summing move quantities or amounts overflowed `uint256`.
+    #[error("arithmetic overflow while summing amounts")]
+    ArithmeticOverflow,
+}
+
+macro_rules! revert {
+    ($name:ident { $($body:tt)* }) => {
+        All::AllErrors::$name(All::$name { $($body)* })
+    };
+}
+
+impl TryFrom<SolutionValidationError> for All::AllErrors {
+    type Error = SolutionValidationError;
+
+    /// Fails for errors that are a Solidity panic rather than a custom error on-chain.
+    fn try_from(err: SolutionValidationError) -> Result<Self, Self::Error> {
+        use SolutionValidationError as E;
+        let idx = |i: usize| U256::from(i);
+        Ok(match err {
+            E::EmptySolution => revert!(SolutionValidator__EmptySolution {}),
+            E::SolutionMustHaveReceiptOutputs => {
+                revert!(SolutionValidator__SolutionMustHaveReceiptOutputs {})
+            }
+            E::DuplicateIntentId(intent_id) => {
+                revert!(SolutionValidator__DuplicateIntentId {
+                    intentId: intent_id
+                })
+            }
+            E::IntentNotFound(intent_id) => {
+                revert!(IntentBook__IntentNotFound {
+                    intentId: intent_id
+                })
+            }
+            E::MoveRecordSrcIdxOutOfBounds(i) => {
+                revert!(SolutionValidator__MoveRecordSrcIdxOutOfBounds { moveIdx: idx(i) })
+            }
+            E::MoveRecordOutputIdxOutOfBounds(i) => {
+                revert!(SolutionValidator__MoveRecordOutputIdxOutOfBounds { moveIdx: idx(i) })
+            }
+            E::FillRecordInIdxOutOfBounds(i) => {
+                revert!(SolutionValidator__FillRecordInIdxOutOfBounds { fillIdx: idx(i) })
+            }
+            E::FillRecordOutputIdxOutOfBounds(i) => {
+                revert!(SolutionValidator__FillRecordOutputIdxOutOfBounds { fillIdx: idx(i) })
+            }
+            E::InputOutputTokenTypeMismatch {
+                input_m_token,
+                output_m_token,
+            } => revert!(SolutionLib__InputOutputTokenTypeMismatch {
+                inputMToken: input_m_token,
+                outputMToken: output_m_token,
+            }),
+            E::InvalidMoveRecord {
+                intent_idx,
+                move_idx,
+            } => revert!(SolutionValidator__InvalidMoveRecord {
+                intentIdx: idx(intent_idx),
+                moveIdx: idx(move_idx),
+            }),
+            E::IntentMustSpendMToken(i) => {
+                revert!(SolutionValidator__IntentMustSpendMToken { intentIdx: idx(i) })
+            }
+            E::IntentTokenBurnt(i) => {
+                revert!(SolutionValidator__IntentTokenBurnt { intentIdx: idx(i) })
+            }
+            E::IntentTokenDoubleSpent(i) => {
+                revert!(SolutionValidator__IntentTokenDoubleSpent { intentIdx: idx(i) })
+            }
+            E::IntentAmountMismatch => revert!(SolutionLib__IntentAmountMismatch {}),
+            E::ReceiptAmountMismatch => revert!(SolutionLib__ReceiptAmountMismatch {}),
+            E::OrphanIntentOutput(i) => {
+                revert!(SolutionValidator__OrphanIntentOutput { intentIdx: idx(i) })
+            }
+            E::OrphanReceiptOutput(i) => {
+                revert!(SolutionValidator__OrphanReceiptOutput { receiptIdx: idx(i) })
+            }
+            E::InputOutputMismatch {
+                sum_input,
+                sum_intent_output,
+                sum_receipt_output,
+            } => revert!(SolutionLib__InputOutputMismatch {
+                sumInput: sum_input,
+                sumIntentOutput: sum_intent_output,
+                sumReceiptOutput: sum_receipt_output,
+            }),
+            E::UnfilledInputIntent(i) => {
+                revert!(SolutionValidator__UnfilledInputIntent { intentIdx: idx(i) })
+            }
+            E::MismatchBetweenInputAndOutputOwners => {
+                revert!(SolutionLib__MismatchBetweenInputAndOutputOwners {})
+            }
+            E::ReceiptMTokenNotFoundInIntentOutcome {
+                intent_idx,
+                receipt_idx,
+            } => revert!(SolutionValidator__ReceiptMTokenNotFoundInIntentOutcome {
+                intentIdx: idx(intent_idx),
+                receiptIdx: idx(receipt_idx),
+            }),
+            E::ChildIntentAuthorMismatch(i) => {
+                revert!(SolutionValidator__ChildIntentAuthorMismatch { intentIdx: idx(i) })
+            }
+            E::ChildIntentMTokenMismatch(i) => {
+                revert!(SolutionValidator__ChildIntentMTokenMismatch { intentIdx: idx(i) })
+            }
+            E::ChildIntentValidBeforeMismatch(i) => {
+                revert!(SolutionValidator__ChildIntentValidBeforeMismatch { intentIdx: idx(i) })
+            }
+            E::ChildIntentValidAfterMismatch(i) => {
+                revert!(SolutionValidator__ChildIntentValidAfterMismatch { intentIdx: idx(i) })
+            }
+            E::ChildIntentInvalidNonce(i) => {
+                revert!(SolutionValidator__ChildIntentInvalidNonce { intentIdx: idx(i) })
+            }
+            E::ChildIntentOutcomeMismatch(i) => {
+                revert!(SolutionValidator__ChildIntentOutcomeMismatch { intentIdx: idx(i) })
+            }
+            E::DuplicateOutputIntentNonce(i1, i2) => {
+                revert!(SolutionValidator__DuplicateOutputIntentNonce {
+                    intentIdx1: idx(i1),
+                    intentIdx2: idx(i2),
+                })
+            }
+            E::MultipleMTokensFilledForAnySingleIntent(i) => {
+                revert!(
+                    SolutionValidator__IntentFillError__MultipleMTokensFilledForAnySingleIntent {
+                        intentIdx: idx(i),
+                    }
+                )
+            }
+            E::NoMTokensFilledForAnySingleIntent(i) => {
+                revert!(
+                    SolutionValidator__IntentFillError__NoMTokensFilledForAnySingleIntent {
+                        intentIdx: idx(i),
+                    }
+                )
+            }
+            E::ExactIntentMustSpendAllMTokens(i) => {
+                revert!(SolutionValidator__ExactIntentMustSpendAllMTokens { intentIdx: idx(i) })
+            }
+            E::ExactIntentNotSatisfied(i) => {
+                revert!(SolutionValidator__ExactIntentNotSatisfied { intentIdx: idx(i) })
+            }
+            E::MinimumFillAmountNotSatisfied {
+                minimum_receipt_amount,
+                actual_receipt_amount,
+            } => revert!(SolutionLib__MinimumFillAmountNotSatisfied {
+                minimumReceiptAmount: minimum_receipt_amount,
+                actualReceiptAmount: actual_receipt_amount,
+            }),
+            E::InsufficientReceiptsToFillPASIntent {
+                expected_receipt_total,
+                actual_receipt_total,
+            } => revert!(SolutionLib__InsufficientReceiptsToFillPASIntent {
+                expectedReceiptTotal: expected_receipt_total,
+                actualReceiptTotal: actual_receipt_total,
+            }),
+            E::UnsupportedFillStructure(i) => {
+                revert!(SolutionValidator__UnsupportedFillStructure { intentIdx: idx(i) })
+            }
+            E::ArithmeticOverflow => return Err(err),
+        })
+    }
+}
+
+/// `a + b`, failing like the checked arithmetic of the contracts.
+fn checked_add(a: U256, b: U256) -> Result<U256, SolutionValidationError> {
+    a.checked_add(b)
+        .ok_or(SolutionValidationError::ArithmeticOverflow)
+}
+
+/// `ceil(a * b / c)`, saturating to `U256::MAX` on overflow.
+fn mul_div_ceil(a: U256, b: U256, c: U256) -> U256 {
+    match a.checked_mul(b) {
+        Some(product) => product.div_ceil(c),
+        None => U256::MAX,
+    }
+}
+
+impl Solution {
+    /// Checks the solution against the given input intents the same way the `SolutionValidator`
+    /// and `SolutionLib` contracts do.
+    ///
+    /// `intents` must contain every intent referenced by `intent_ids`, in any order.
+    pub fn validate(&self, intents: &[Intent]) -> Result<(), SolutionValidationError> {
+        use SolutionValidationError as E;
+
+        if self.intent_ids.is_empty() {
+            return Err(E::EmptySolution);
+        }
+        if self.receipt_outputs.is_empty() {
+            return Err(E::SolutionMustHaveReceiptOutputs);
+        }
+
+        let mut seen = HashSet::new();
+        for id in &self.intent_ids {
+            if !seen.insert(*id) {
+                return Err(E::DuplicateIntentId(*id));
+            }
+        }
+        let by_id: HashMap<B256, &Intent> = intents.iter().map(|i| (i.intent_id(), i)).collect();
+        let inputs = self
+            .intent_ids
+            .iter()
+            .map(|id| by_id.get(id).copied().ok_or(E::IntentNotFound(*id)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Fill graph: which outputs are delivered to which input intent.
+        let mut child_parent: HashMap<usize, usize> = HashMap::new();
+        let mut receipts_for: Vec<Vec<usize>> = vec![Vec::new(); inputs.len()];
+        let mut children_of: Vec<Vec<usize>> = vec![Vec::new(); inputs.len()];
+        for (fill_idx, fill) in self.fill_graph.iter().enumerate() {
+            let in_idx = fill.in_idx as usize;
+            let out_idx = fill.out_idx as usize;
+            if in_idx >= inputs.len() {
+                return Err(E::FillRecordInIdxOutOfBounds(fill_idx));
+            }
+            match fill.out_type {
+                OutType::Intent => {
+                    if out_idx >= self.intent_outputs.len() {
+                        return Err(E::FillRecordOutputIdxOutOfBounds(fill_idx));
+                    }
+                    child_parent.insert(out_idx, in_idx);
+                    children_of[in_idx].push(out_idx);
+                }
+                OutType::Receipt => {
+                    if out_idx >= self.receipt_outputs.len() {
+                        return Err(E::FillRecordOutputIdxOutOfBounds(fill_idx));
+                    }
+                    receipts_for[in_idx].push(out_idx);
+                }
+            }
+        }
+
+        // Spend graph: where each input intent's mTokens go.
+        let mut spent = vec![U256::ZERO; inputs.len()];
+        let mut spent_to_receipts = vec![U256::ZERO; inputs.len()];
+        let mut moved_into_intent = vec![U256::ZERO; self.intent_outputs.len()];
+        let mut moved_into_receipt = vec![U256::ZERO; self.receipt_outputs.len()];
+        let mut funded_receipts = HashSet::new();
+        for (move_idx, mv) in self.spend_graph.iter().enumerate() {
+            let src_idx = mv.src_idx as usize;
+            let out_idx = mv.output_idx.out_idx as usize;
+            let Some(src) = inputs.get(src_idx) else {
+                return Err(E::MoveRecordSrcIdxOutOfBounds(move_idx));
+            };
+            let output_m_token = match mv.output_idx.out_type {
+                OutType::Intent => {
+                    let Some(out) = self.intent_outputs.get(out_idx) else {
+                        return Err(E::MoveRecordOutputIdxOutOfBounds(move_idx));
+                    };
+                    if child_parent.get(&out_idx) != Some(&src_idx) {
+                        return Err(E::InvalidMoveRecord {
+                            intent_idx: src_idx,
+                            move_idx,
+                        });
+                    }
+                    moved_into_intent[out_idx] = checked_add(moved_into_intent[out_idx], mv.qty)?;
+                    out.src_m_token
+                }
+                OutType::Receipt => {
+                    let Some(out) = self.receipt_outputs.get(out_idx) else {
+                        return Err(E::MoveRecordOutputIdxOutOfBounds(move_idx));
+                    };
+                    moved_into_receipt[out_idx] = checked_add(moved_into_receipt[out_idx], mv.qty)?;
+                    spent_to_receipts[src_idx] = checked_add(spent_to_receipts[src_idx], mv.qty)?;
+                    funded_receipts.insert(out_idx);
+                    out.m_token
+                }
+            };
+            if output_m_token != src.src_m_token {
+                return Err(E::InputOutputTokenTypeMismatch {
+                    input_m_token: src.src_m_token,
+                    output_m_token,
+                });
+            }
+            spent[src_idx] = checked_add(spent[src_idx], mv.qty)?;
+        }
+
+        for (i, input) in inputs.iter().enumerate() {
+            if spent[i].is_zero() {
+                return Err(E::IntentMustSpendMToken(i));
+            }
+            if spent[i] < input.src_amount {
+                return Err(E::IntentTokenBurnt(i));
+            }
+            if spent[i] > input.src_amount {
+                return Err(E::IntentTokenDoubleSpent(i));
+            }
+        }
+        for (k, out) in self.intent_outputs.iter().enumerate() {
+            if !child_parent.contains_key(&k) {
+                return Err(E::OrphanIntentOutput(k));
+            }
+            if moved_into_intent[k] != out.src_amount {
+                return Err(E::IntentAmountMismatch);
+            }
+        }
+        for (r, receipt) in self.receipt_outputs.iter().enumerate() {
+            if !funded_receipts.contains(&r) {
+                return Err(E::OrphanReceiptOutput(r));
+            }
+            if moved_into_receipt[r] != receipt.m_token_amount {
+                return Err(E::ReceiptAmountMismatch);
+            }
+        }
+        self.check_token_sums(&inputs)?;
+        self.check_child_intents(&inputs, &child_parent)?;
+
+        for (i, input) in inputs.iter().enumerate() {
+            if receipts_for[i].is_empty() && children_of[i].is_empty() {
+                return Err(E::UnfilledInputIntent(i));
+            }
+            let mut received: BTreeMap<Address, U256> = BTreeMap::new();
+            for &r in &receipts_for[i] {
+                let receipt = &self.receipt_outputs[r];
+                if receipt.owner != input.author {
+                    return Err(E::MismatchBetweenInputAndOutputOwners);
+                }
+                if !input.outcome.m_tokens.contains(&receipt.m_token) {
+                    return Err(E::ReceiptMTokenNotFoundInIntentOutcome {
+                        intent_idx: i,
+                        receipt_idx: r,
+                    });
+                }
+                let total = received.entry(receipt.m_token).or_default();
+                *total = checked_add(*total, receipt.m_token_amount)?;
+            }
+            check_fill(
+                i,
+                input,
+                spent_to_receipts[i],
+                !children_of[i].is_empty(),
+                &received,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn check_token_sums(&self, inputs: &[&Intent]) -> Result<(), SolutionValidationError> {
+        let mut sums: BTreeMap<Address, (U256, U256, U256)> = BTreeMap::new();
+        for input in inputs {
+            let sum = &mut sums.entry(input.src_m_token).or_default().0;
+            *sum = checked_add(*sum, input.src_amount)?;
+        }
+        for out in &self.intent_outputs {
+            let sum = &mut sums.entry(out.src_m_token).or_default().1;
+            *sum = checked_add(*sum, out.src_amount)?;
+        }
+        for receipt in &self.receipt_outputs {
+            let sum = &mut sums.entry(receipt.m_token).or_default().2;
+            *sum = checked_add(*sum, receipt.m_token_amount)?;
+        }
+        for (sum_input, sum_intent_output, sum_receipt_output) in sums.into_values() {
+            if sum_input != checked_add(sum_intent_output, sum_receipt_output)? {
+                return Err(SolutionValidationError::InputOutputMismatch {
+                    sum_input,
+                    sum_intent_output,
+                    sum_receipt_output,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_child_intents(
+        &self,
+        inputs: &[&Intent],
+        child_parent: &HashMap<usize, usize>,
+    ) -> Result<(), SolutionValidationError> {
+        use SolutionValidationError as E;
+
+        let mut nonces: HashMap<(Address, U256), usize> = HashMap::new();
+        for (k, child) in self.intent_outputs.iter().enumerate() {
+            if let Some(other) = nonces.insert((child.author, child.nonce), k) {
+                return Err(E::DuplicateOutputIntentNonce(other, k));
+            }
+            let parent = inputs[child_parent[&k]];
+            if child.author != parent.author {
+                return Err(E::ChildIntentAuthorMismatch(k));
+            }
+            if child.src_m_token != parent.src_m_token {
+                return Err(E::ChildIntentMTokenMismatch(k));
+            }
+            if child.valid_before != parent.valid_before {
+                return Err(E::ChildIntentValidBeforeMismatch(k));
+            }
+            if child.valid_after != parent.valid_after {
+                return Err(E::ChildIntentValidAfterMismatch(k));
+            }
+            if child.nonce <= parent.nonce {
+                return Err(E::ChildIntentInvalidNonce(k));
+            }
+            let (c, p) = (&child.outcome, &parent.outcome);
+            let same_shape = c.m_tokens == p.m_tokens
+                && c.m_amounts.len() == p.m_amounts.len()
+                && c.outcome_asset_structure == p.outcome_asset_structure
+                && c.fill_structure == p.fill_structure;
+            // The remainder must not ask for less than the parent's price.
+            let fair_amounts = c
+                .m_amounts
+                .iter()
+                .zip(&p.m_amounts)
+                .all(|(&ca, &pa)| mul_div_ceil(pa, child.src_amount, parent.src_amount) <= ca);
+            if !same_shape || !fair_amounts {
+                return Err(E::ChildIntentOutcomeMismatch(k));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the receipts delivered to input intent `i` satisfy its outcome for the
+/// `spent` source amount.
+fn check_fill(
+    i: usize,
+    input: &Intent,
+    spent: U256,
+    partially_filled: bool,
+    received: &BTreeMap<Address, U256>,
+) -> Result<(), SolutionValidationError> {
+    use SolutionValidationError as E;
+
+    let outcome = &input.outcome;
+    match outcome.fill_structure {
+        FillStructure::Exact if partially_filled => {
+            return Err(E::ExactIntentMustSpendAllMTokens(i));
+        }
+        FillStructure::ConcreteRange => return Err(E::UnsupportedFillStructure(i)),
+        _ => {}
+    }
+    if received.is_empty() {
+        return Err(E::NoMTokensFilledForAnySingleIntent(i));
+    }
+    if outcome.outcome_asset_structure == OutcomeAssetStructure::AnySingle && received.len() > 1 {
+        return Err(E::MultipleMTokensFilledForAnySingleIntent(i));
+    }
+
+    // Outcome amounts owed pro rata for the spent part of the source amount.
+    let required: Vec<(Address, U256, U256)> = outcome
+        .m_tokens
+        .iter()
+        .zip(&outcome.m_amounts)
+        .map(|(token, &amount)| {
+            let owed = mul_div_ceil(amount, spent, input.src_amount);
+            let got = received.get(token).copied().unwrap_or_default();
+            (*token, owed, got)
+        })
+        .collect();
+    let shortfall = match outcome.outcome_asset_structure {
+        OutcomeAssetStructure::AnySingle | OutcomeAssetStructure::All => required
+            .iter()
+            .find(|(token, owed, got)| {
+                (outcome.outcome_asset_structure == OutcomeAssetStructure::All
+                    || received.contains_key(token))
+                    && got < owed
+            })
+            .map(|(_, owed, got)| (*owed, *got)),
+        OutcomeAssetStructure::Any => {
+            let filled: U256 = required
+                .iter()
+                .filter(|(_, owed, _)| !owed.is_zero())
+                .map(|(_, owed, got)| mul_div_ceil(*got, WAD, *owed))
+                .fold(U256::ZERO, |acc, x| acc.saturating_add(x));
+            // Report the first outcome token; there is none if `m_amounts` is empty.
+            (filled < WAD).then(|| {
+                required
+                    .first()
+                    .map(|(_, owed, got)| (*owed, *got))
+                    .unwrap_or_default()
+            })
+        }
+    };
+    match (shortfall, &outcome.fill_structure) {
+        (None, _) => Ok(()),
+        (Some(_), FillStructure::Exact) => Err(E::ExactIntentNotSatisfied(i)),
+        (Some((owed, got)), FillStructure::Minimum) => Err(E::MinimumFillAmountNotSatisfied {
+            minimum_receipt_amount: owed,
+            actual_receipt_amount: got,
+        }),
+        (Some((owed, got)), _) => Err(E::InsufficientReceiptsToFillPASIntent {
+            expected_receipt_total: owed,
+            actual_receipt_total: got,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::receipt::Receipt;
+    use crate::types::solution::{FillRecord, MoveRecord, OutputIdx};
+
+    fn swap(author: Address, sell: Address, sell_amt: u64, buy: Address, buy_amt: u64) -> Intent {
+        Intent::simple_swap(
+            author,
+            U256::from(2_000_000_000_u64),
+            U256::ZERO,
+            None,
+            sell,
+            U256::from(sell_amt),
+            buy,
+            U256::from(buy_amt),
+        )
+    }
+
+    fn matched_pair() -> (Vec<Intent>, Solution) {
+        let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let (usdc, eth) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+        let a = swap(alice, usdc, 3000, eth, 1);
+        let b = swap(bob, eth, 1, usdc, 3000);
+        let receipt = |m_token, amt: u64, owner, intent: &Intent| Receipt {
+            m_token,
+            m_token_amount: U256::from(amt),
+            owner,
+            intent_hash: intent.intent_id(),
+        };
+        let mv = |src_idx, out_idx, qty: u64| MoveRecord {
+            src_idx,
+            output_idx: OutputIdx {
+                out_type: OutType::Receipt,
+                out_idx,
+            },
+            qty: U256::from(qty),
+        };
+        let fill = |in_idx, out_idx| FillRecord {
+            in_idx,
+            out_idx,
+            out_type: OutType::Receipt,
+        };
+        let solution = Solution {
+            intent_ids: vec![a.intent_id(), b.intent_id()],
+            intent_outputs: vec![],
+            receipt_outputs: vec![receipt(eth, 1, alice, &a), receipt(usdc, 3000, bob, &b)],
+            spend_graph: vec![mv(0, 1, 3000), mv(1, 0, 1)],
+            fill_graph: vec![fill(0, 0), fill(1, 1)],
+        };
+        (vec![a, b], solution)
+    }
+
+    #[test]
+    fn test_valid_coincidence_of_wants() {
+        let (intents, solution) = matched_pair();
+        assert_eq!(solution.validate(&intents), Ok(()));
+    }
+
+    #[test]
+    fn test_out_of_bounds_move_record() {
+        let (intents, mut solution) = matched_pair();
+        solution.spend_graph[1].output_idx.out_idx = 5;
+        let err = solution.validate(&intents).unwrap_err();
+        assert_eq!(
+            err,
+            SolutionValidationError::MoveRecordOutputIdxOutOfBounds(1)
+        );
+        assert!(matches!(
+            All::AllErrors::try_from(err),
+            Ok(All::AllErrors::SolutionValidator__MoveRecordOutputIdxOutOfBounds(_))
+        ));
+    }
+
+    #[test]
+    fn test_wrapping_move_quantities() {
+        // Quantities that sum to exactly the expected amounts modulo 2^256.
+        let (intents, mut solution) = matched_pair();
+        solution.spend_graph[0].qty = U256::MAX;
+        let mut wrap = solution.spend_graph[0].clone();
+        wrap.qty = U256::from(3001);
+        solution.spend_graph.push(wrap);
+        assert_eq!(
+            solution.validate(&intents),
+            Err(SolutionValidationError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_any_outcome_without_amounts() {
+        let (mut intents, mut solution) = matched_pair();
+        intents[0].outcome.outcome_asset_structure = OutcomeAssetStructure::Any;
+        intents[0].outcome.m_amounts.clear();
+        solution.intent_ids[0] = intents[0].intent_id();
+        assert_eq!(
+            solution.validate(&intents),
+            Err(SolutionValidationError::ExactIntentNotSatisfied(0))
+        );
+    }
+
+    #[test]
+    fn test_exact_intent_not_satisfied() {
+        let (mut intents, mut solution) = matched_pair();
+        intents[0].outcome.m_amounts[0] = U256::from(2);
+        solution.intent_ids[0] = intents[0].intent_id();
+        assert_eq!(
+            solution.validate(&intents),
+            Err(SolutionValidationError::ExactIntentNotSatisfied(0))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_intent_id() {
+        let (intents, mut solution) = matched_pair();
+        solution.intent_ids[1] = solution.intent_ids[0];
+        assert_eq!(
+            solution.validate(&intents),
+            Err(SolutionValidationError::DuplicateIntentId(
+                solution.intent_ids[0]
+            ))
+        );
+    }
+}
@@ -1,6 +1,9 @@
-use alloy::primitives::Address;
+use alloy::primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
 
+use super::common::{MTOKEN_DECIMALS, RoundingMode, TokenAmt, TokenAmtError};
+use super::token_registry::TokenRegistryError;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Token {
     pub spoke_address: Address,
@@ -9,3 +12,48 @@ pub struct Token {
     pub symbol: String,
     pub decimals: u8,
 }
+
+impl Token {
+    /// The spoke chain id as used by the contracts, which store it as a `u32`.
+    pub fn spoke_chain_id_u32(&self) -> Result<u32, TokenRegistryError> {
+        self.spoke_chain_id
+            .try_into()
+            .map_err(|_| TokenRegistryError::ChainIdOutOfRange {
+                symbol: self.symbol.clone(),
+                chain_id: self.spoke_chain_id,
+            })
+    }
+
+    /// `amt` base units of the spoke token.
+    pub fn amount(&self, amt: U256) -> TokenAmt {
+        TokenAmt::new(amt, self.decimals)
+    }
+
+    /// Parses a human-readable amount of the spoke token, e.g. `"12.5"`.
+    pub fn parse_amount(&self, amount: &str) -> Result<TokenAmt, TokenAmtError> {
+        TokenAmt::parse(amount, self.decimals)
+    }
+
+    /// Converts spoke token base units into mToken base units (18 decimals).
+    pub fn spoke_to_mtoken(
+        &self,
+        spoke_amt: U256,
+        rounding: RoundingMode,
+    ) -> Result<U256, TokenAmtError> {
+        Ok(self
+            .amount(spoke_amt)
+            .rescale(MTOKEN_DECIMALS, rounding)?
+            .amt)
+    }
+
+    /// Converts mToken base units (18 decimals) into spoke token base units.
+    pub fn mtoken_to_spoke(
+        &self,
+        mtoken_amt: U256,
+        rounding: RoundingMode,
+    ) -> Result<U256, TokenAmtError> {
+        Ok(TokenAmt::new(mtoken_amt, MTOKEN_DECIMALS)
+            .rescale(self.decimals, rounding)?
+            .amt)
+    }
+}
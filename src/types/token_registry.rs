@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use serde::{Deserialize, Serialize};
+
+use super::common::MTOKEN_DECIMALS;
+use super::sol_types::{ERC20, MToken};
+use super::token::Token;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenRegistryError {
+    #[error("failed to read token list: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid JSON token list: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid TOML token list: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("unsupported token list format: {0}")]
+    UnsupportedFormat(String),
+    #[error("duplicate symbol {symbol} on chain {chain_id}")]
+    DuplicateSymbol { symbol: String, chain_id: u64 },
+    #[error("duplicate mToken {0}")]
+    DuplicateMToken(Address),
+    #[error("duplicate spoke token {address} on chain {chain_id}")]
+    DuplicateSpokeToken { chain_id: u64, address: Address },
+    #[error("{symbol} has spoke chain id {chain_id}, which does not fit in a u32")]
+    ChainIdOutOfRange { symbol: String, chain_id: u64 },
+    #[error("{symbol} at {address} has {actual} decimals on-chain, expected {expected}")]
+    DecimalsMismatch {
+        symbol: String,
+        address: Address,
+        expected: u8,
+        actual: u8,
+    },
+    #[error(transparent)]
+    Contract(#[from] alloy::contract::Error),
+}
+
+/// The on-disk format of a token list, e.g. in TOML:
+///
+/// ```toml
+/// [[tokens]]
+/// symbol = "USDC"
+/// decimals = 6
+/// spoke_chain_id = 1
+/// spoke_address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+/// mtoken_address = "0x..."
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TokenList {
+    pub tokens: Vec<Token>,
+}
+
+/// The tokens known to Arcadia, indexed by symbol, mToken and spoke token.
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: Vec<Token>,
+    by_mtoken: HashMap<Address, usize>,
+    by_spoke: HashMap<(u64, Address), usize>,
+    by_symbol: HashMap<(String, u64), usize>,
+}
+
+impl TokenRegistry {
+    pub fn new(tokens: impl IntoIterator<Item = Token>) -> Result<Self, TokenRegistryError> {
+        let mut registry = Self::default();
+        for token in tokens {
+            registry.insert(token)?;
+        }
+        Ok(registry)
+    }
+
+    pub fn from_json_str(s: &str) -> Result<Self, TokenRegistryError> {
+        let list: TokenList = serde_json::from_str(s)?;
+        Self::new(list.tokens)
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, TokenRegistryError> {
+        let list: TokenList = toml::from_str(s)?;
+        Self::new(list.tokens)
+    }
+
+    /// Loads a `.json` or `.toml` token list.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TokenRegistryError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&content),
+            Some("toml") => Self::from_toml_str(&content),
+            _ => Err(TokenRegistryError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    /// Adds a token. Symbols must be unique per chain, mTokens and spoke tokens must be unique,
+    /// and the spoke chain id must fit the `u32` used on-chain.
+    pub fn insert(&mut self, token: Token) -> Result<(), TokenRegistryError> {
+        token.spoke_chain_id_u32()?;
+        let symbol_key = (token.symbol.clone(), token.spoke_chain_id);
+        let spoke_key = (token.spoke_chain_id, token.spoke_address);
+        if self.by_symbol.contains_key(&symbol_key) {
+            return Err(TokenRegistryError::DuplicateSymbol {
+                symbol: token.symbol,
+                chain_id: token.spoke_chain_id,
+            });
+        }
+        if self.by_mtoken.contains_key(&token.mtoken_address) {
+            return Err(TokenRegistryError::DuplicateMToken(token.mtoken_address));
+        }
+        if self.by_spoke.contains_key(&spoke_key) {
+            return Err(TokenRegistryError::DuplicateSpokeToken {
+                chain_id: token.spoke_chain_id,
+                address: token.spoke_address,
+            });
+        }
+        let idx = self.tokens.len();
+        self.by_symbol.insert(symbol_key, idx);
+        self.by_mtoken.insert(token.mtoken_address, idx);
+        self.by_spoke.insert(spoke_key, idx);
+        self.tokens.push(token);
+        Ok(())
+    }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn by_mtoken(&self, mtoken_address: Address) -> Option<&Token> {
+        self.by_mtoken
+            .get(&mtoken_address)
+            .map(|&idx| &self.tokens[idx])
+    }
+
+    pub fn by_spoke(&self, chain_id: u64, spoke_address: Address) -> Option<&Token> {
+        self.by_spoke
+            .get(&(chain_id, spoke_address))
+            .map(|&idx| &self.tokens[idx])
+    }
+
+    pub fn by_symbol_on_chain(&self, symbol: &str, chain_id: u64) -> Option<&Token> {
+        self.by_symbol
+            .get(&(symbol.to_string(), chain_id))
+            .map(|&idx| &self.tokens[idx])
+    }
+
+    /// Every token with `symbol`, one per spoke chain it is deployed on.
+    pub fn by_symbol(&self, symbol: &str) -> Vec<&Token> {
+        self.tokens
+            .iter()
+            .filter(|token| token.symbol == symbol)
+            .collect()
+    }
+
+    pub fn on_chain(&self, chain_id: u64) -> Vec<&Token> {
+        self.tokens
+            .iter()
+            .filter(|token| token.spoke_chain_id == chain_id)
+            .collect()
+    }
+
+    /// Checks the configured decimals of every token on `chain_id` against `ERC20::decimals`.
+    pub async fn validate_spoke_decimals<P: Provider>(
+        &self,
+        chain_id: u64,
+        provider: &P,
+    ) -> Result<(), TokenRegistryError> {
+        for token in self.on_chain(chain_id) {
+            let actual = ERC20::new(token.spoke_address, provider)
+                .decimals()
+                .call()
+                .await?;
+            check_decimals(token, token.spoke_address, token.decimals, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every mToken reports [`MTOKEN_DECIMALS`] through `MToken::decimals`.
+    /// `provider` must be connected to Arcadia.
+    pub async fn validate_mtoken_decimals<P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<(), TokenRegistryError> {
+        for token in &self.tokens {
+            let actual = MToken::new(token.mtoken_address, provider)
+                .decimals()
+                .call()
+                .await?;
+            check_decimals(token, token.mtoken_address, MTOKEN_DECIMALS, actual)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_decimals(
+    token: &Token,
+    address: Address,
+    expected: u8,
+    actual: u8,
+) -> Result<(), TokenRegistryError> {
+    if expected != actual {
+        return Err(TokenRegistryError::DecimalsMismatch {
+            symbol: token.symbol.clone(),
+            address,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKENS: &str = r#"
+        [[tokens]]
+        symbol = "USDC"
+        decimals = 6
+        spoke_chain_id = 1
+        spoke_address = "0x1111111111111111111111111111111111111111"
+        mtoken_address = "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+
+        [[tokens]]
+        symbol = "USDC"
+        decimals = 6
+        spoke_chain_id = 42161
+        spoke_address = "0x1111111111111111111111111111111111111111"
+        mtoken_address = "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
+    "#;
+
+    #[test]
+    fn test_load_and_lookup() {
+        let registry = TokenRegistry::from_toml_str(TOKENS).unwrap();
+        assert_eq!(registry.by_symbol("USDC").len(), 2);
+        let token = registry
+            .by_spoke(42161, Address::repeat_byte(0x11))
+            .unwrap();
+        assert_eq!(token.mtoken_address, Address::repeat_byte(0xbb));
+        assert_eq!(
+            registry.by_mtoken(Address::repeat_byte(0xaa)),
+            registry.by_symbol_on_chain("USDC", 1)
+        );
+
+        let json = serde_json::to_string(&TokenList {
+            tokens: registry.tokens().to_vec(),
+        })
+        .unwrap();
+        assert_eq!(TokenRegistry::from_json_str(&json).unwrap().len(), 2);
+
+        let mut duplicate = registry.tokens()[0].clone();
+        duplicate.spoke_chain_id = 10;
+        assert!(matches!(
+            TokenRegistry::new([registry.tokens()[0].clone(), duplicate]),
+            Err(TokenRegistryError::DuplicateMToken(_))
+        ));
+
+        let mut out_of_range = registry.tokens()[0].clone();
+        out_of_range.spoke_chain_id = u64::from(u32::MAX) + 1;
+        assert!(matches!(
+            TokenRegistry::new([out_of_range]),
+            Err(TokenRegistryError::ChainIdOutOfRange { .. })
+        ));
+    }
+}